@@ -1,7 +1,6 @@
 use alloy_primitives::{keccak256, I256, U256};
 use alloy_sol_types::SolValue;
 use criterion::{criterion_group, criterion_main, Criterion};
-use uniswap_v3_math::{swap_math, utils::ruint_to_u256};
 use uniswap_v3_sdk::utils::compute_swap_step;
 
 fn pseudo_random(seed: u64) -> U256 {
@@ -51,8 +50,10 @@ fn compute_swap_step_benchmark(c: &mut Criterion) {
     });
 }
 
+#[cfg(feature = "extensions")]
 fn compute_swap_step_benchmark_ref(c: &mut Criterion) {
     use ethers::types::{I256, U256};
+    use uniswap_v3_math::{swap_math, utils::ruint_to_u256};
 
     let inputs: Vec<(U256, U256, u128, I256, u32)> = generate_inputs()
         .into_iter()
@@ -88,9 +89,12 @@ fn compute_swap_step_benchmark_ref(c: &mut Criterion) {
     });
 }
 
+#[cfg(feature = "extensions")]
 criterion_group!(
     benches,
     compute_swap_step_benchmark,
     compute_swap_step_benchmark_ref,
 );
+#[cfg(not(feature = "extensions"))]
+criterion_group!(benches, compute_swap_step_benchmark);
 criterion_main!(benches);
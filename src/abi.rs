@@ -0,0 +1,80 @@
+//! Solidity interfaces of the periphery contracts that the SDK encodes calldata for
+
+use alloy_sol_types::sol;
+
+sol! {
+    interface IMulticall {
+        function multicall(bytes[] calldata data) external payable returns (bytes[] memory results);
+    }
+
+    interface IPeripheryPaymentsWithFee {
+        function unwrapWETH9(uint256 amountMinimum, address recipient) external payable;
+
+        function refundETH() external payable;
+
+        function sweepToken(address token, uint256 amountMinimum, address recipient) external payable;
+
+        function unwrapWETH9WithFee(
+            uint256 amountMinimum,
+            address recipient,
+            uint256 feeBips,
+            address feeRecipient
+        ) external payable;
+
+        function sweepTokenWithFee(
+            address token,
+            uint256 amountMinimum,
+            address recipient,
+            uint256 feeBips,
+            address feeRecipient
+        ) external payable;
+    }
+
+    interface ISwapRouter {
+        struct ExactInputSingleParams {
+            address tokenIn;
+            address tokenOut;
+            uint24 fee;
+            address recipient;
+            uint256 deadline;
+            uint256 amountIn;
+            uint256 amountOutMinimum;
+            uint160 sqrtPriceLimitX96;
+        }
+
+        function exactInputSingle(ExactInputSingleParams calldata params) external payable returns (uint256 amountOut);
+
+        struct ExactInputParams {
+            bytes path;
+            address recipient;
+            uint256 deadline;
+            uint256 amountIn;
+            uint256 amountOutMinimum;
+        }
+
+        function exactInput(ExactInputParams calldata params) external payable returns (uint256 amountOut);
+
+        struct ExactOutputSingleParams {
+            address tokenIn;
+            address tokenOut;
+            uint24 fee;
+            address recipient;
+            uint256 deadline;
+            uint256 amountOut;
+            uint256 amountInMaximum;
+            uint160 sqrtPriceLimitX96;
+        }
+
+        function exactOutputSingle(ExactOutputSingleParams calldata params) external payable returns (uint256 amountIn);
+
+        struct ExactOutputParams {
+            bytes path;
+            address recipient;
+            uint256 deadline;
+            uint256 amountOut;
+            uint256 amountInMaximum;
+        }
+
+        function exactOutput(ExactOutputParams calldata params) external payable returns (uint256 amountIn);
+    }
+}
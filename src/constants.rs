@@ -1,4 +1,8 @@
+use crate::error::Error;
 use alloy_primitives::{address, b256, Address, B256};
+use serde::{Deserialize, Serialize};
+use std::hash::{Hash, Hasher};
+use uniswap_sdk_core::prelude::{Fraction, Percent};
 
 pub const FACTORY_ADDRESS: Address = address!("1F98431c8aD98523631AE4a59f267346ea31F984");
 
@@ -7,16 +11,68 @@ pub const ADDRESS_ZERO: Address = Address::ZERO;
 pub const POOL_INIT_CODE_HASH: B256 =
     b256!("e34f199b19b2b4f47f68442619d555527d244f78a3297ea89325f843f87b8b54");
 
-/// The default factory enabled fee amounts, denominated in hundredths of bips.
+/// The maximum number of hops a trade found by the best trade search may contain.
+pub const MAX_HOPS: usize = 3;
+
+/// Whether a trade is an exact input or exact output trade.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TradeType {
+    ExactInput,
+    ExactOutput,
+}
+
+/// The default factory enabled fee amounts, denominated in hundredths of bips.
+///
+/// Fee amounts compare and hash by their fee and tick spacing, so a [`FeeAmount::Custom`] with the
+/// values of a default tier equals the named variant.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum FeeAmount {
-    LOWEST = 100,
-    LOW = 500,
-    MEDIUM = 3000,
-    HIGH = 10000,
+    LOWEST,
+    LOW,
+    MEDIUM,
+    HIGH,
+    /// A fee tier enabled on some deployments only, as `(fee, tick_spacing)` with the fee in
+    /// hundredths of bips. Prefer [`FeeAmount::new`], which validates the values.
+    Custom(u32, i32),
 }
 
 impl FeeAmount {
+    /// Returns the fee tier with the given fee and tick spacing, the named variant for a default
+    /// factory tier and [`FeeAmount::Custom`] otherwise
+    ///
+    /// # Arguments
+    ///
+    /// * `fee`: The fee in hundredths of bips, which must be below 100% like in the factory's
+    ///   `enableFeeAmount`
+    /// * `tick_spacing`: The tick spacing, which must be in `1..16384` like in the factory's
+    ///   `enableFeeAmount`
+    ///
+    /// returns: Result<FeeAmount, Error>
+    ///
+    pub const fn new(fee: u32, tick_spacing: i32) -> Result<Self, Error> {
+        if fee >= 1_000_000 || tick_spacing <= 0 || tick_spacing >= 16384 {
+            return Err(Error::InvalidFeeAmount(fee, tick_spacing));
+        }
+        Ok(match (fee, tick_spacing) {
+            (100, 1) => Self::LOWEST,
+            (500, 10) => Self::LOW,
+            (3000, 60) => Self::MEDIUM,
+            (10000, 200) => Self::HIGH,
+            _ => Self::Custom(fee, tick_spacing),
+        })
+    }
+
+    /// The fee in hundredths of bips.
+    pub const fn fee(&self) -> u32 {
+        match self {
+            Self::LOWEST => 100,
+            Self::LOW => 500,
+            Self::MEDIUM => 3000,
+            Self::HIGH => 10000,
+            Self::Custom(fee, _) => *fee,
+        }
+    }
+
     /// The default factory tick spacings by fee amount.
     pub const fn tick_spacing(&self) -> i32 {
         match self {
@@ -24,8 +80,34 @@ impl FeeAmount {
             Self::LOW => 10,
             Self::MEDIUM => 60,
             Self::HIGH => 200,
+            Self::Custom(_, tick_spacing) => *tick_spacing,
         }
     }
+
+    /// The fee as a fraction of the amount, e.g. 3/1000 for `MEDIUM`.
+    pub fn as_fraction(&self) -> Fraction {
+        Fraction::new(self.fee(), 1_000_000)
+    }
+
+    /// The fee as a percentage, e.g. 0.3% for `MEDIUM`.
+    pub fn as_percent(&self) -> Percent {
+        Percent::new(self.fee(), 1_000_000)
+    }
+}
+
+impl PartialEq for FeeAmount {
+    fn eq(&self, other: &Self) -> bool {
+        self.fee() == other.fee() && self.tick_spacing() == other.tick_spacing()
+    }
+}
+
+impl Eq for FeeAmount {}
+
+impl Hash for FeeAmount {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.fee().hash(state);
+        self.tick_spacing().hash(state);
+    }
 }
 
 impl From<i32> for FeeAmount {
@@ -39,3 +121,62 @@ impl From<i32> for FeeAmount {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uniswap_sdk_core::constants::Rounding;
+
+    #[test]
+    fn fee_amount_as_percent() {
+        assert_eq!(FeeAmount::MEDIUM.as_percent(), Percent::new(3, 1000));
+        assert_eq!(FeeAmount::HIGH.as_percent(), Percent::new(1, 100));
+        assert_eq!(
+            FeeAmount::LOW
+                .as_percent()
+                .to_fixed(2, Rounding::RoundHalfUp),
+            "0.05"
+        );
+        assert_eq!(FeeAmount::LOWEST.as_fraction(), Fraction::new(1, 10_000));
+    }
+
+    #[test]
+    fn custom_fee_amount() {
+        let fee = FeeAmount::Custom(250, 5);
+        assert_eq!(fee.fee(), 250);
+        assert_eq!(fee.tick_spacing(), 5);
+        assert_eq!(fee.as_percent(), Percent::new(1, 4000));
+        assert_eq!(FeeAmount::MEDIUM.fee(), 3000);
+    }
+
+    #[test]
+    fn fee_amount_new() {
+        assert!(matches!(FeeAmount::new(500, 10), Ok(FeeAmount::LOW)));
+        assert!(matches!(
+            FeeAmount::new(250, 5),
+            Ok(FeeAmount::Custom(250, 5))
+        ));
+        for (fee, tick_spacing) in [
+            (1_000_000, 10),
+            (1 << 24, 10),
+            (500, 0),
+            (500, -10),
+            (500, 16384),
+        ] {
+            assert!(matches!(
+                FeeAmount::new(fee, tick_spacing),
+                Err(Error::InvalidFeeAmount(f, s)) if f == fee && s == tick_spacing
+            ));
+        }
+    }
+
+    #[test]
+    fn custom_fee_amount_equals_named_tier() {
+        use std::collections::HashSet;
+
+        assert_eq!(FeeAmount::Custom(500, 10), FeeAmount::LOW);
+        assert_ne!(FeeAmount::Custom(500, 5), FeeAmount::LOW);
+        let tiers: HashSet<_> = [FeeAmount::LOW, FeeAmount::Custom(500, 10)].into();
+        assert_eq!(tiers.len(), 1);
+    }
+}
@@ -2,11 +2,21 @@ mod pool;
 mod position;
 mod route;
 mod tick;
+mod tick_bitmap_data_provider;
 mod tick_data_provider;
 mod tick_list_data_provider;
+mod trade;
 
-pub use pool::Pool;
+#[cfg(test)]
+pub(crate) use pool::full_range_pool;
+pub use pool::{
+    best_pool_for_amount, token_value_in, DetailedOutputAmount, Pool, PoolState, SwapContext,
+    SwapTrace,
+};
 pub use position::{MintAmounts, Position};
+pub use route::{CompactRoute, Route};
 pub use tick::{Tick, TickTrait};
+pub use tick_bitmap_data_provider::TickBitmapDataProvider;
 pub use tick_data_provider::*;
 pub use tick_list_data_provider::TickListDataProvider;
+pub use trade::{trade_comparator, BestTradeOptions, Swap, Trade};
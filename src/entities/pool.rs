@@ -1,4 +1,4 @@
-use crate::prelude::*;
+use crate::{error::Error, prelude::*};
 use alloy_primitives::{Address, B256, I256, U256};
 use anyhow::Result;
 use num_bigint::BigUint;
@@ -17,9 +17,120 @@ pub struct Pool {
     pub sqrt_ratio_x96: U256,
     pub liquidity: u128,
     pub tick_current: i32,
+    /// The denominators of the protocol fee share of the swap fee for token0 and token1, where
+    /// zero disables the protocol fee. Only splits the swap fee between LPs and the protocol, so
+    /// it does not change swap amounts. Set it with [`Pool::set_fee_protocol`], as swaps error with
+    /// [`Error::InvalidFeeProtocol`] for a value the pool contract would not accept.
+    pub fee_protocol: (u8, u8),
     pub tick_data_provider: Arc<dyn TickDataProvider<Tick = Tick>>,
     _token0_price: Option<Price<Token, Token>>,
     _token1_price: Option<Price<Token, Token>>,
+    _address: CachedAddress,
+}
+
+/// The default factory address of a pool, with the identity it was computed for, so that a change
+/// of the public identity fields is not answered with a stale address
+#[derive(Clone, Copy)]
+struct CachedAddress {
+    token0: Address,
+    token1: Address,
+    fee: FeeAmount,
+    address: Address,
+}
+
+#[cfg(test)]
+thread_local! {
+    static ADDRESS_COMPUTATIONS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+/// A pool at a 1:1 price with the given liquidity over the full range of usable ticks, shared by
+/// the tests across the crate
+#[cfg(test)]
+pub(crate) fn full_range_pool(
+    token_a: &Token,
+    token_b: &Token,
+    fee: FeeAmount,
+    liquidity: u128,
+) -> Pool {
+    Pool::new(
+        token_a.clone(),
+        token_b.clone(),
+        fee,
+        encode_sqrt_ratio_x96(1, 1),
+        liquidity,
+        Some(Arc::new(TickListDataProvider::new(
+            vec![
+                Tick::new(
+                    nearest_usable_tick(MIN_TICK, fee.tick_spacing()),
+                    liquidity,
+                    liquidity as i128,
+                ),
+                Tick::new(
+                    nearest_usable_tick(MAX_TICK, fee.tick_spacing()),
+                    liquidity,
+                    -(liquidity as i128),
+                ),
+            ],
+            fee.tick_spacing(),
+        ))),
+    )
+    .unwrap()
+}
+
+/// The full result of a swap simulation, including the initialized ticks crossed along the way
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SwapTrace {
+    /// The delta of the balance of token0 of the pool, exact when negative, minimum when positive
+    pub amount0: I256,
+    /// The delta of the balance of token1 of the pool, exact when negative, minimum when positive
+    pub amount1: I256,
+    pub sqrt_price_after: U256,
+    pub tick_after: i32,
+    pub liquidity_after: u128,
+    /// The initialized ticks crossed during the swap, in the order they were crossed
+    pub crossed_ticks: Vec<i32>,
+    /// The portion of the swap fee, in the input token, accrued to the protocol rather than LPs
+    pub protocol_fee: U256,
+}
+
+/// The state of a pool that changes as a result of a swap
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PoolState {
+    pub sqrt_ratio_x96: U256,
+    pub liquidity: u128,
+    pub tick_current: i32,
+}
+
+/// The result of [`Pool::get_output_amount_detailed`]
+#[derive(Clone, Debug)]
+pub struct DetailedOutputAmount {
+    pub output_amount: CurrencyAmount<Token>,
+    /// The pool with state updated after the swap
+    pub pool: Pool,
+    /// Whether the swap stopped at the sqrt price limit, in which case the input amount may not
+    /// have been fully consumed
+    pub limit_reached: bool,
+}
+
+/// Reusable state for swap simulations run in a loop, e.g. by backtesters. A simulation with a
+/// context reuses its buffer of crossed ticks and keeps the pool state after the swap in the
+/// context instead of constructing a new pool. See [`Pool::get_output_amount_with_context`].
+#[derive(Clone, Debug, Default)]
+pub struct SwapContext {
+    crossed_ticks: Vec<i32>,
+    pool_state: Option<PoolState>,
+}
+
+impl SwapContext {
+    /// The initialized ticks crossed by the last swap simulated with this context
+    pub fn crossed_ticks(&self) -> &[i32] {
+        &self.crossed_ticks
+    }
+
+    /// The state of the pool after the last swap simulated with this context, if any
+    pub fn pool_state(&self) -> Option<PoolState> {
+        self.pool_state
+    }
 }
 
 struct SwapState {
@@ -58,14 +169,70 @@ impl Pool {
         )
     }
 
+    /// Returns the address of the pool deployed by the default factory, computed when the pool is
+    /// constructed and again only if `token0`, `token1` or `fee` were changed since. Use
+    /// [`Pool::get_address`] for other factories.
+    pub fn address(&self) -> Address {
+        let cached = &self._address;
+        if cached.token0 == self.token0.address()
+            && cached.token1 == self.token1.address()
+            && cached.fee == self.fee
+        {
+            cached.address
+        } else {
+            Self::default_address(&self.token0, &self.token1, self.fee).address
+        }
+    }
+
+    fn default_address(token0: &Token, token1: &Token, fee: FeeAmount) -> CachedAddress {
+        #[cfg(test)]
+        ADDRESS_COMPUTATIONS.with(|count| count.set(count.get() + 1));
+        CachedAddress {
+            token0: token0.address(),
+            token1: token1.address(),
+            fee,
+            address: Self::get_address(token0, token1, fee, None, None),
+        }
+    }
+
+    fn state(&self) -> PoolState {
+        PoolState {
+            sqrt_ratio_x96: self.sqrt_ratio_x96,
+            liquidity: self.liquidity,
+            tick_current: self.tick_current,
+        }
+    }
+
+    /// Returns a copy of the pool with the state after a swap, keeping the cached address. The
+    /// tick is taken from the swap rather than the price, as a swap that stops on an initialized
+    /// tick it crossed downwards leaves the tick below it, like the pool contract.
+    fn with_state(&self, state: PoolState) -> Self {
+        if state == self.state() {
+            return self.clone();
+        }
+        Self {
+            sqrt_ratio_x96: state.sqrt_ratio_x96,
+            liquidity: state.liquidity,
+            tick_current: state.tick_current,
+            _token0_price: None,
+            _token1_price: None,
+            ..self.clone()
+        }
+    }
+
     /// Construct a pool
     ///
+    /// The tokens may be given in either order. They are sorted by address into `token0` and
+    /// `token1` as in the pool contract, and `sqrt_ratio_x96` always refers to the sorted pair, so
+    /// it is never inverted.
+    ///
     /// # Arguments
     ///
     /// * `token_a`: One of the tokens in the pool
     /// * `token_b`: The other token in the pool
-    /// * `fee`: The fee in hundredths of a bips of the input amount of every swap that is collected by the pool
-    /// * `sqrt_ratio_x96`: The sqrt of the current ratio of amounts of token1 to token0
+    /// * `fee`: The fee in hundredths of a bips of the input amount of every swap that is collected by the pool,
+    ///   where a custom fee amount must be valid for [`FeeAmount::new`]
+    /// * `sqrt_ratio_x96`: The sqrt of the current ratio of amounts of token1 to token0 of the sorted pair
     /// * `liquidity`: The current value of in range liquidity
     /// * `tick_current`: The current tick of the pool
     /// * `tick_data_provider`: A tick data provider that can return tick data
@@ -77,24 +244,156 @@ impl Pool {
         liquidity: u128,
         tick_data_provider: Option<Arc<dyn TickDataProvider<Tick = Tick>>>,
     ) -> Result<Self> {
+        if token_a.chain_id() != token_b.chain_id() {
+            return Err(Error::ChainIdIsDifferent.into());
+        }
+        if token_a.address() == token_b.address() {
+            return Err(Error::IdenticalTokens.into());
+        }
+        if let FeeAmount::Custom(fee, tick_spacing) = fee {
+            FeeAmount::new(fee, tick_spacing)?;
+        }
         let (token0, token1) = if token_a.sorts_before(&token_b)? {
             (token_a, token_b)
         } else {
             (token_b, token_a)
         };
         Ok(Self {
+            _address: Self::default_address(&token0, &token1, fee),
             token0,
             token1,
             fee,
             sqrt_ratio_x96,
             liquidity,
             tick_current: get_tick_at_sqrt_ratio(sqrt_ratio_x96)?,
+            fee_protocol: (0, 0),
             tick_data_provider: tick_data_provider.unwrap_or(Arc::new(NoTickDataProvider)),
             _token0_price: None,
             _token1_price: None,
         })
     }
 
+    /// Construct a pool from a `U256` liquidity, as read from chain or the subgraph, erroring with
+    /// [`Error::LiquidityOverflow`] instead of truncating if it does not fit in a `u128`. See
+    /// [`Pool::new`] for the other arguments.
+    pub fn try_new_from_u256_liquidity(
+        token_a: Token,
+        token_b: Token,
+        fee: FeeAmount,
+        sqrt_ratio_x96: U256,
+        liquidity: U256,
+        tick_data_provider: Option<Arc<dyn TickDataProvider<Tick = Tick>>>,
+    ) -> Result<Self> {
+        let liquidity = u128::try_from(liquidity).map_err(|_| Error::LiquidityOverflow)?;
+        Self::new(
+            token_a,
+            token_b,
+            fee,
+            sqrt_ratio_x96,
+            liquidity,
+            tick_data_provider,
+        )
+    }
+
+    /// Construct a pool from the result of a `slot0` call and the liquidity of the pool, as decoded
+    /// by the caller's own RPC layer. Unlike [`Pool::new`], the tick is taken from `slot0`, which
+    /// may be one below the tick of the sqrt price if the price is exactly at a tick crossed
+    /// downwards.
+    ///
+    /// # Arguments
+    ///
+    /// * `token_a`: One of the tokens in the pool
+    /// * `token_b`: The other token in the pool
+    /// * `fee`: The fee tier of the pool
+    /// * `slot0`: The `sqrtPriceX96` and `tick` of `slot0`
+    /// * `liquidity`: The current value of in range liquidity
+    /// * `tick_data_provider`: A tick data provider that can return tick data
+    ///
+    /// returns: Pool, or [`Error::InconsistentTick`] if the tick does not match the sqrt price
+    ///
+    pub fn from_slot0(
+        token_a: Token,
+        token_b: Token,
+        fee: FeeAmount,
+        (sqrt_price_x96, tick): (U256, i32),
+        liquidity: u128,
+        tick_data_provider: Option<Arc<dyn TickDataProvider<Tick = Tick>>>,
+    ) -> Result<Self> {
+        let mut pool = Self::new(
+            token_a,
+            token_b,
+            fee,
+            sqrt_price_x96,
+            liquidity,
+            tick_data_provider,
+        )?;
+        pool.update_state(sqrt_price_x96, liquidity, tick)?;
+        Ok(pool)
+    }
+
+    /// Construct a pool from the JSON of a pool entity of the Uniswap V3 subgraph, including its
+    /// `ticks`
+    ///
+    /// # Arguments
+    ///
+    /// * `value`: The subgraph pool with `feeTier`, `sqrtPrice`, `liquidity`, `tick` and `ticks`,
+    ///   where each tick has `tickIdx`, `liquidityGross` and `liquidityNet`
+    /// * `token0`: One of the tokens in the pool
+    /// * `token1`: The other token in the pool
+    ///
+    /// returns: Pool
+    ///
+    pub fn from_subgraph_json(
+        value: &serde_json::Value,
+        token0: Token,
+        token1: Token,
+    ) -> Result<Self> {
+        fn field<T: FromStr>(value: &serde_json::Value, key: &str) -> Result<T, Error> {
+            let invalid = || Error::InvalidSubgraphData(key.to_string());
+            match &value[key] {
+                serde_json::Value::String(s) => s.parse().map_err(|_| invalid()),
+                serde_json::Value::Number(n) => n.to_string().parse().map_err(|_| invalid()),
+                _ => Err(invalid()),
+            }
+        }
+
+        let fee = match field::<u32>(value, "feeTier")? {
+            100 => FeeAmount::LOWEST,
+            500 => FeeAmount::LOW,
+            3000 => FeeAmount::MEDIUM,
+            10000 => FeeAmount::HIGH,
+            _ => return Err(Error::InvalidSubgraphData("feeTier".to_string()).into()),
+        };
+        let ticks = value["ticks"]
+            .as_array()
+            .ok_or_else(|| Error::InvalidSubgraphData("ticks".to_string()))?
+            .iter()
+            .map(|tick| {
+                Ok((
+                    field(tick, "tickIdx")?,
+                    field(tick, "liquidityGross")?,
+                    field(tick, "liquidityNet")?,
+                ))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        // a partial page of ticks is not a valid tick list, so check it instead of panicking
+        let tick_data_provider = TickListDataProvider::from_tuples(ticks, fee.tick_spacing())
+            .map_err(|err| Error::InvalidSubgraphData(format!("ticks: {err}")))?;
+        let sqrt_price_x96 = field(value, "sqrtPrice")?;
+        let liquidity = field(value, "liquidity")?;
+        let mut pool = Self::new(
+            token0,
+            token1,
+            fee,
+            sqrt_price_x96,
+            liquidity,
+            Some(Arc::new(tick_data_provider)),
+        )?;
+        pool.update_state(sqrt_price_x96, liquidity, field(value, "tick")?)
+            .map_err(|_| Error::InvalidSubgraphData("tick".to_string()))?;
+        Ok(pool)
+    }
+
     pub fn chain_id(&self) -> u32 {
         self.token0.chain_id()
     }
@@ -162,6 +461,53 @@ impl Pool {
         }
     }
 
+    /// Updates the state of the pool in place, e.g. from the `Swap` events of the pool, and clears
+    /// the cached prices
+    ///
+    /// # Arguments
+    ///
+    /// * `sqrt_price_x96`: The new sqrt price of the pool
+    /// * `liquidity`: The new in range liquidity
+    /// * `tick_current`: The new current tick, which must be the tick of the sqrt price, or the
+    ///   tick below it if the price is exactly at a tick crossed downwards
+    ///
+    pub fn update_state(
+        &mut self,
+        sqrt_price_x96: U256,
+        liquidity: u128,
+        tick_current: i32,
+    ) -> Result<(), Error> {
+        let tick = get_tick_at_sqrt_ratio(sqrt_price_x96)?;
+        let consistent = tick_current == tick
+            || (tick_current == tick - 1 && get_sqrt_ratio_at_tick(tick)? == sqrt_price_x96);
+        if !consistent {
+            return Err(Error::InconsistentTick(tick_current));
+        }
+        self.sqrt_ratio_x96 = sqrt_price_x96;
+        self.liquidity = liquidity;
+        self.tick_current = tick_current;
+        self._token0_price = None;
+        self._token1_price = None;
+        Ok(())
+    }
+
+    /// Sets the protocol fee of the pool, validated like the pool contract's `setFeeProtocol`
+    ///
+    /// # Arguments
+    ///
+    /// * `fee_protocol0`: The denominator of the protocol fee share for token0, 0 or in `4..=10`
+    /// * `fee_protocol1`: The denominator of the protocol fee share for token1, 0 or in `4..=10`
+    ///
+    pub fn set_fee_protocol(&mut self, fee_protocol0: u8, fee_protocol1: u8) -> Result<(), Error> {
+        for fee_protocol in [fee_protocol0, fee_protocol1] {
+            if !is_valid_fee_protocol(fee_protocol) {
+                return Err(Error::InvalidFeeProtocol(fee_protocol));
+            }
+        }
+        self.fee_protocol = (fee_protocol0, fee_protocol1);
+        Ok(())
+    }
+
     /// Given an input amount of a token, return the computed output amount, and a pool with state updated after the trade
     ///
     /// # Arguments
@@ -176,42 +522,272 @@ impl Pool {
         input_amount: CurrencyAmount<Token>,
         sqrt_price_limit_x96: Option<U256>,
     ) -> Result<(CurrencyAmount<Token>, Self)> {
+        let mut ctx = SwapContext::default();
+        let output_amount =
+            self.get_output_amount_with_context(input_amount, sqrt_price_limit_x96, &mut ctx)?;
+        let pool = match ctx.pool_state {
+            Some(state) => self.with_state(state),
+            None => self.clone(),
+        };
+        Ok((output_amount, pool))
+    }
+
+    /// Same as [`Pool::get_output_amount`], but for an input amount of any currency, e.g. native
+    /// ether, which is wrapped before the simulation. The output amount is in the other token of
+    /// the pool.
+    ///
+    /// # Arguments
+    ///
+    /// * `input_amount`: The input amount, whose currency must wrap to a token of the pool
+    /// * `sqrt_price_limit_x96`: The Q64.96 sqrt price limit
+    ///
+    /// returns: The output amount and the pool with updated state
+    ///
+    pub fn get_output_amount_from_currency(
+        &self,
+        input_amount: &CurrencyAmount<impl CurrencyTrait>,
+        sqrt_price_limit_x96: Option<U256>,
+    ) -> Result<(CurrencyAmount<Token>, Self)> {
+        self.get_output_amount(input_amount.wrapped()?, sqrt_price_limit_x96)
+    }
+
+    /// Same as [`Pool::get_output_amount`], but reuses the given context, which avoids allocating
+    /// in loops that run many simulations. Instead of a pool with updated state, the state after
+    /// the swap is recorded in the context, see [`SwapContext::pool_state`].
+    ///
+    /// # Arguments
+    ///
+    /// * `input_amount`: The input amount for which to quote the output amount
+    /// * `sqrt_price_limit_x96`: The Q64.96 sqrt price limit
+    /// * `ctx`: The context to reuse, which is reset by the call
+    ///
+    /// returns: The output amount
+    ///
+    pub fn get_output_amount_with_context(
+        &self,
+        input_amount: CurrencyAmount<Token>,
+        sqrt_price_limit_x96: Option<U256>,
+        ctx: &mut SwapContext,
+    ) -> Result<CurrencyAmount<Token>> {
         assert!(self.involves_token(&input_amount.meta.currency), "TOKEN");
 
         let zero_for_one = input_amount.meta.currency.equals(&self.token0);
+        let output_token = if zero_for_one {
+            self.token1.clone()
+        } else {
+            self.token0.clone()
+        };
+        if input_amount.quotient().is_zero() {
+            ctx.crossed_ticks.clear();
+            ctx.pool_state = Some(self.state());
+            return Ok(CurrencyAmount::from_raw_amount(output_token, 0)?);
+        }
 
-        let (output_amount, sqrt_ratio_x96, liquidity, _) = self._swap(
+        let (output_amount, _) = self.output_amount_raw_in_context(
+            amount_specified(&input_amount.quotient())?.into_raw(),
             zero_for_one,
-            big_int_to_i256(input_amount.quotient()),
             sqrt_price_limit_x96,
+            ctx,
         )?;
-        let output_token = if zero_for_one {
-            self.token1.clone()
+        Ok(CurrencyAmount::from_raw_amount(
+            output_token,
+            u256_to_big_int(output_amount),
+        )?)
+    }
+
+    /// Same as [`Pool::get_output_amount`], but also reports whether the swap stopped at the sqrt
+    /// price limit, which is the given limit or, if none is given, the price bound of the swap
+    /// direction, reached when the swap consumes all the liquidity
+    ///
+    /// # Arguments
+    ///
+    /// * `input_amount`: The input amount for which to quote the output amount
+    /// * `sqrt_price_limit_x96`: The Q64.96 sqrt price limit
+    ///
+    pub fn get_output_amount_detailed(
+        &self,
+        input_amount: CurrencyAmount<Token>,
+        sqrt_price_limit_x96: Option<U256>,
+    ) -> Result<DetailedOutputAmount> {
+        const ONE: U256 = U256::from_limbs([1, 0, 0, 0]);
+        let zero_for_one = input_amount.meta.currency.equals(&self.token0);
+        let limit = sqrt_price_limit_x96.unwrap_or(if zero_for_one {
+            MIN_SQRT_RATIO + ONE
         } else {
-            self.token0.clone()
+            MAX_SQRT_RATIO - ONE
+        });
+        let (output_amount, pool) = self.get_output_amount(input_amount, sqrt_price_limit_x96)?;
+        Ok(DetailedOutputAmount {
+            output_amount,
+            limit_reached: pool.sqrt_ratio_x96 == limit,
+            pool,
+        })
+    }
+
+    /// Returns the output amount of an exact input swap against the pool, as the quoter's
+    /// `quoteExactInputSingle`
+    ///
+    /// # Arguments
+    ///
+    /// * `amount_in`: The input amount
+    /// * `sqrt_price_limit_x96`: The Q64.96 sqrt price limit
+    ///
+    /// returns: The output amount
+    ///
+    pub fn quote_exact_input_single(
+        &self,
+        amount_in: CurrencyAmount<Token>,
+        sqrt_price_limit_x96: Option<U256>,
+    ) -> Result<CurrencyAmount<Token>> {
+        Ok(self.get_output_amount(amount_in, sqrt_price_limit_x96)?.0)
+    }
+
+    /// Returns the input amount required by an exact output swap against the pool, as the
+    /// quoter's `quoteExactOutputSingle`
+    ///
+    /// # Arguments
+    ///
+    /// * `amount_out`: The output amount
+    /// * `sqrt_price_limit_x96`: The Q64.96 sqrt price limit
+    ///
+    /// returns: The input amount
+    ///
+    pub fn quote_exact_output_single(
+        &self,
+        amount_out: CurrencyAmount<Token>,
+        sqrt_price_limit_x96: Option<U256>,
+    ) -> Result<CurrencyAmount<Token>> {
+        Ok(self.get_input_amount(amount_out, sqrt_price_limit_x96)?.0)
+    }
+
+    /// Returns the largest input amount of `input_token` whose swap against the pool has a price
+    /// impact, including the fee, under `max_impact`, found by binary search. Returns zero if even
+    /// the smallest amounts, which lose most of their output to rounding, exceed it.
+    ///
+    /// # Arguments
+    ///
+    /// * `input_token`: The token to swap into the pool
+    /// * `max_impact`: The price impact threshold
+    ///
+    /// returns: The largest input amount under the threshold
+    ///
+    pub fn max_amount_in_for_price_impact(
+        &self,
+        input_token: &Token,
+        max_impact: Percent,
+    ) -> Result<CurrencyAmount<Token>> {
+        assert!(self.involves_token(input_token), "TOKEN");
+        let under = |amount: &BigInt| -> Result<bool> {
+            Ok(self.price_impact_of(input_token, amount.clone())? < max_impact)
         };
-        Ok((
-            CurrencyAmount::from_raw_amount(output_token, i256_to_big_int(output_amount.neg()))?,
-            Pool::new(
-                self.token0.clone(),
-                self.token1.clone(),
-                self.fee,
-                sqrt_ratio_x96,
-                liquidity,
-                Some(self.tick_data_provider.clone()),
-            )?,
+        let two = BigInt::from(2);
+        let limit = BigInt::from(u128::MAX);
+
+        // small amounts lose most of their output to rounding, so find one under the threshold
+        let mut lo = BigInt::from(1);
+        while !under(&lo)? {
+            lo *= &two;
+            if lo > limit {
+                return Ok(CurrencyAmount::from_raw_amount(input_token.clone(), 0)?);
+            }
+        }
+        let mut hi = &lo * &two;
+        while under(&hi)? {
+            lo = hi.clone();
+            hi *= &two;
+            if hi > limit {
+                return Ok(CurrencyAmount::from_raw_amount(input_token.clone(), lo)?);
+            }
+        }
+        while &hi - &lo > BigInt::from(1) {
+            let mid: BigInt = (&lo + &hi) / &two;
+            if under(&mid)? {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+        Ok(CurrencyAmount::from_raw_amount(input_token.clone(), lo)?)
+    }
+
+    /// The price impact of swapping the raw `amount_in` of `input_token` against the pool
+    fn price_impact_of(&self, input_token: &Token, amount_in: BigInt) -> Result<Percent> {
+        let amount_in = CurrencyAmount::from_raw_amount(input_token.clone(), amount_in)?;
+        let (amount_out, _) = self.get_output_amount(amount_in.clone(), None)?;
+        let quoted_amount_out = self.clone().price_of(input_token).quote(amount_in)?;
+        let price_impact = quoted_amount_out
+            .subtract(&amount_out)?
+            .divide(&quoted_amount_out)?;
+        Ok(Percent::new(
+            price_impact.numerator(),
+            price_impact.denominator(),
         ))
     }
 
+    /// Given a raw input amount, return the raw output amount and the pool state after the trade,
+    /// without allocating any currency amounts or pools
+    ///
+    /// # Arguments
+    ///
+    /// * `amount_in`: The raw input amount
+    /// * `zero_for_one`: Whether the input is token0, i.e. the direction of the swap
+    /// * `sqrt_price_limit_x96`: The Q64.96 sqrt price limit
+    ///
+    /// returns: The raw output amount and the pool state after the trade
+    ///
+    pub fn get_output_amount_raw(
+        &self,
+        amount_in: U256,
+        zero_for_one: bool,
+        sqrt_price_limit_x96: Option<U256>,
+    ) -> Result<(U256, PoolState)> {
+        self.output_amount_raw_in_context(
+            amount_in,
+            zero_for_one,
+            sqrt_price_limit_x96,
+            &mut SwapContext::default(),
+        )
+    }
+
+    /// Runs [`Pool::get_output_amount_raw`] in the given context, recording the state after the
+    /// swap in it. All the exact input output amount methods go through this.
+    fn output_amount_raw_in_context(
+        &self,
+        amount_in: U256,
+        zero_for_one: bool,
+        sqrt_price_limit_x96: Option<U256>,
+        ctx: &mut SwapContext,
+    ) -> Result<(U256, PoolState)> {
+        let trace = self.swap_in_context(
+            zero_for_one,
+            I256::try_from(amount_in).map_err(|_| Error::AmountOverflow)?,
+            sqrt_price_limit_x96,
+            ctx,
+        )?;
+        let state = PoolState {
+            sqrt_ratio_x96: trace.sqrt_price_after,
+            liquidity: trace.liquidity_after,
+            tick_current: trace.tick_after,
+        };
+        ctx.pool_state = Some(state);
+        let output_amount = if zero_for_one {
+            trace.amount1
+        } else {
+            trace.amount0
+        };
+        Ok((output_amount.neg().into_raw(), state))
+    }
+
     /// Given a desired output amount of a token, return the computed input amount and a pool with state updated after the trade
     ///
     /// # Arguments
     ///
     /// * `output_amount`: the output amount for which to quote the input amount
     /// * `sqrt_price_limit_x96`: The Q64.96 sqrt price limit. If zero for one, the price cannot be less than this value
-    /// after the swap. If one for zero, the price cannot be greater than this value after the swap
+    ///   after the swap. If one for zero, the price cannot be greater than this value after the swap
     ///
-    /// returns: The input amount and the pool with updated state
+    /// returns: The input amount and the pool with updated state, or [`Error::InsufficientLiquidity`]
+    /// if no price limit is given and the pool cannot provide the whole output amount
     ///
     pub fn get_input_amount(
         &self,
@@ -221,12 +797,29 @@ impl Pool {
         assert!(self.involves_token(&output_amount.meta.currency), "TOKEN");
 
         let zero_for_one = output_amount.meta.currency.equals(&self.token1);
+        if output_amount.quotient().is_zero() {
+            let input_token = if zero_for_one {
+                self.token0.clone()
+            } else {
+                self.token1.clone()
+            };
+            return Ok((
+                CurrencyAmount::from_raw_amount(input_token, 0)?,
+                self.clone(),
+            ));
+        }
 
-        let (input_amount, sqrt_ratio_x96, liquidity, _) = self._swap(
-            zero_for_one,
-            big_int_to_i256(output_amount.quotient()).neg(),
-            sqrt_price_limit_x96,
-        )?;
+        let amount_out = amount_specified(&output_amount.quotient())?;
+        let trace = self.swap_with_trace(zero_for_one, amount_out.neg(), sqrt_price_limit_x96)?;
+        let (input_amount, output_delta) = if zero_for_one {
+            (trace.amount0, trace.amount1)
+        } else {
+            (trace.amount1, trace.amount0)
+        };
+        // without a price limit, a partial fill means that the pool ran out of liquidity
+        if sqrt_price_limit_x96.is_none() && output_delta.neg() < amount_out {
+            return Err(Error::InsufficientLiquidity.into());
+        }
         let input_token = if zero_for_one {
             self.token0.clone()
         } else {
@@ -234,23 +827,84 @@ impl Pool {
         };
         Ok((
             CurrencyAmount::from_raw_amount(input_token, i256_to_big_int(input_amount))?,
-            Pool::new(
-                self.token0.clone(),
-                self.token1.clone(),
-                self.fee,
-                sqrt_ratio_x96,
-                liquidity,
-                Some(self.tick_data_provider.clone()),
-            )?,
+            self.with_state(PoolState {
+                sqrt_ratio_x96: trace.sqrt_price_after,
+                liquidity: trace.liquidity_after,
+                tick_current: trace.tick_after,
+            }),
+        ))
+    }
+
+    /// Simulates a swap against the pool with the sign conventions of the pool contract's `swap`
+    ///
+    /// # Arguments
+    ///
+    /// * `zero_for_one`: The direction of the swap, true for token0 to token1, false for token1 to token0
+    /// * `amount_specified`: The amount of the swap, which implicitly configures the swap as exact input (positive),
+    ///   or exact output (negative)
+    /// * `sqrt_price_limit_x96`: The Q64.96 sqrt price limit. If zero for one, the price cannot be less than this
+    ///   value after the swap. If one for zero, the price cannot be greater than this value after the swap
+    ///
+    /// returns: (amount0, amount1, sqrt_price_after, liquidity_after, tick_after), where the amounts are the
+    /// deltas of the pool balances
+    ///
+    pub fn swap(
+        &self,
+        zero_for_one: bool,
+        amount_specified: I256,
+        sqrt_price_limit_x96: Option<U256>,
+    ) -> Result<(I256, I256, U256, u128, i32)> {
+        let trace = self.swap_with_trace(zero_for_one, amount_specified, sqrt_price_limit_x96)?;
+        Ok((
+            trace.amount0,
+            trace.amount1,
+            trace.sqrt_price_after,
+            trace.liquidity_after,
+            trace.tick_after,
         ))
     }
 
-    fn _swap(
+    /// Simulates a swap against the pool, returning the same values as the pool contract's `swap`
+    /// along with the initialized ticks that were crossed
+    ///
+    /// # Arguments
+    ///
+    /// * `zero_for_one`: The direction of the swap, true for token0 to token1, false for token1 to token0
+    /// * `amount_specified`: The amount of the swap, which implicitly configures the swap as exact input (positive),
+    ///   or exact output (negative)
+    /// * `sqrt_price_limit_x96`: The Q64.96 sqrt price limit. If zero for one, the price cannot be less than this
+    ///   value after the swap. If one for zero, the price cannot be greater than this value after the swap
+    ///
+    /// returns: Result<SwapTrace>
+    ///
+    pub fn swap_with_trace(
         &self,
         zero_for_one: bool,
         amount_specified: I256,
         sqrt_price_limit_x96: Option<U256>,
-    ) -> Result<(I256, U256, u128, i32)> {
+    ) -> Result<SwapTrace> {
+        let mut ctx = SwapContext::default();
+        let trace = self.swap_in_context(
+            zero_for_one,
+            amount_specified,
+            sqrt_price_limit_x96,
+            &mut ctx,
+        )?;
+        Ok(SwapTrace {
+            crossed_ticks: ctx.crossed_ticks,
+            ..trace
+        })
+    }
+
+    /// Runs the swap loop of [`Pool::swap_with_trace`], recording the crossed ticks in the context
+    /// rather than in the returned trace
+    fn swap_in_context(
+        &self,
+        zero_for_one: bool,
+        amount_specified: I256,
+        sqrt_price_limit_x96: Option<U256>,
+        ctx: &mut SwapContext,
+    ) -> Result<SwapTrace> {
         const ONE: U256 = U256::from_limbs([1, 0, 0, 0]);
         let sqrt_price_limit_x96 = sqrt_price_limit_x96.unwrap_or_else(|| {
             if zero_for_one {
@@ -269,6 +923,16 @@ impl Pool {
         }
 
         let exact_input = amount_specified >= I256::ZERO;
+        let fee_protocol = if zero_for_one {
+            self.fee_protocol.0
+        } else {
+            self.fee_protocol.1
+        };
+        // the field is public, so it may not have been set through `set_fee_protocol`
+        if !is_valid_fee_protocol(fee_protocol) {
+            return Err(Error::InvalidFeeProtocol(fee_protocol).into());
+        }
+        let mut protocol_fee = U256::ZERO;
 
         // keep track of swap state
         let mut state = SwapState {
@@ -278,6 +942,7 @@ impl Pool {
             tick: self.tick_current,
             liquidity: self.liquidity,
         };
+        ctx.crossed_ticks.clear();
 
         // start swap while loop
         while !state.amount_specified_remaining.is_zero()
@@ -304,11 +969,7 @@ impl Pool {
                     self.tick_spacing(),
                 )?;
 
-            if step.tick_next < MIN_TICK {
-                step.tick_next = MIN_TICK;
-            } else if step.tick_next > MAX_TICK {
-                step.tick_next = MAX_TICK;
-            }
+            step.tick_next = step.tick_next.clamp(MIN_TICK, MAX_TICK);
 
             step.sqrt_price_next_x96 = get_sqrt_ratio_at_tick(step.tick_next)?;
             (
@@ -325,7 +986,7 @@ impl Pool {
                 },
                 state.liquidity,
                 state.amount_specified_remaining,
-                self.fee as u32,
+                self.fee.fee(),
             )?;
 
             if exact_input {
@@ -342,6 +1003,13 @@ impl Pool {
                 );
             }
 
+            // if the protocol fee is on, calculate how much is owed and decrement the LP fee
+            if fee_protocol > 0 {
+                let delta = step.fee_amount / U256::from(fee_protocol);
+                step.fee_amount -= delta;
+                protocol_fee += delta;
+            }
+
             if state.sqrt_price_x96 == step.sqrt_price_next_x96 {
                 // if the tick is initialized, run the tick transition
                 if step.initialized {
@@ -355,6 +1023,7 @@ impl Pool {
                         liquidity_net = liquidity_net.neg();
                     }
                     state.liquidity = add_delta(state.liquidity, liquidity_net)?;
+                    ctx.crossed_ticks.push(step.tick_next);
                 }
                 state.tick = step.tick_next - zero_for_one as i32;
             } else {
@@ -363,13 +1032,256 @@ impl Pool {
             }
         }
 
+        let amount_specified_used = amount_specified - state.amount_specified_remaining;
+        let (amount0, amount1) = if zero_for_one == exact_input {
+            (amount_specified_used, state.amount_calculated)
+        } else {
+            (state.amount_calculated, amount_specified_used)
+        };
+        Ok(SwapTrace {
+            amount0,
+            amount1,
+            sqrt_price_after: state.sqrt_price_x96,
+            tick_after: state.tick,
+            liquidity_after: state.liquidity,
+            crossed_ticks: Vec::new(),
+            protocol_fee,
+        })
+    }
+
+    /// Computes the input and output amounts of the swap that moves the pool price exactly to the
+    /// sqrt price of the target tick, in the direction implied by the target tick
+    ///
+    /// # Arguments
+    ///
+    /// * `target_tick`: The tick at which the swap should end, strictly between `MIN_TICK` and
+    ///   `MAX_TICK` and not at the current price
+    ///
+    /// returns: The input amount and the output amount of the swap, or
+    /// [`Error::TickOutOfRange`] or [`Error::TickAtCurrentPrice`] for an invalid target tick
+    ///
+    pub fn amount_to_reach_tick(
+        &self,
+        target_tick: i32,
+    ) -> Result<(CurrencyAmount<Token>, CurrencyAmount<Token>)> {
+        if target_tick <= MIN_TICK || target_tick >= MAX_TICK {
+            return Err(Error::TickOutOfRange(target_tick).into());
+        }
+        let sqrt_price_target_x96 = get_sqrt_ratio_at_tick(target_tick)?;
+        if sqrt_price_target_x96 == self.sqrt_ratio_x96 {
+            return Err(Error::TickAtCurrentPrice(target_tick).into());
+        }
+
+        let zero_for_one = sqrt_price_target_x96 < self.sqrt_ratio_x96;
+        let trace = self.swap_with_trace(zero_for_one, I256::MAX, Some(sqrt_price_target_x96))?;
+        let (input_token, output_token, amount_in, amount_out) = if zero_for_one {
+            (&self.token0, &self.token1, trace.amount0, trace.amount1)
+        } else {
+            (&self.token1, &self.token0, trace.amount1, trace.amount0)
+        };
         Ok((
-            state.amount_calculated,
-            state.sqrt_price_x96,
-            state.liquidity,
-            state.tick,
+            CurrencyAmount::from_raw_amount(input_token.clone(), i256_to_big_int(amount_in))?,
+            CurrencyAmount::from_raw_amount(
+                output_token.clone(),
+                i256_to_big_int(amount_out.neg()),
+            )?,
         ))
     }
+
+    /// Computes the active liquidity at the given tick by walking the initialized ticks from the
+    /// current tick and applying their net liquidity
+    ///
+    /// # Arguments
+    ///
+    /// * `tick`: The tick at which to compute the active liquidity
+    ///
+    /// returns: The liquidity that would be in range if the pool's current tick were `tick`
+    ///
+    pub fn liquidity_at_tick(&self, tick: i32) -> Result<u128> {
+        assert!((MIN_TICK..=MAX_TICK).contains(&tick), "TICK");
+        let mut liquidity = self.liquidity;
+        let mut current = self.tick_current;
+        if tick >= self.tick_current {
+            loop {
+                let (next, initialized) = self
+                    .tick_data_provider
+                    .next_initialized_tick_within_one_word(current, false, self.tick_spacing())?;
+                if next > tick {
+                    break;
+                }
+                if initialized {
+                    let liquidity_net = self.tick_data_provider.get_tick(next)?.liquidity_net;
+                    liquidity = add_delta(liquidity, liquidity_net)?;
+                }
+                current = next;
+            }
+        } else {
+            loop {
+                let (next, initialized) = self
+                    .tick_data_provider
+                    .next_initialized_tick_within_one_word(current, true, self.tick_spacing())?;
+                if next <= tick {
+                    break;
+                }
+                if initialized {
+                    let liquidity_net = self.tick_data_provider.get_tick(next)?.liquidity_net;
+                    liquidity = add_delta(liquidity, liquidity_net.neg())?;
+                }
+                current = next - 1;
+            }
+        }
+        Ok(liquidity)
+    }
+
+    /// Computes the active liquidity to the right of each initialized tick in a range, i.e. the
+    /// liquidity in range once the price has crossed the tick upwards, for rendering a depth chart
+    ///
+    /// # Arguments
+    ///
+    /// * `tick_lower`: The lowest tick of the range, inclusive
+    /// * `tick_upper`: The highest tick of the range, inclusive
+    ///
+    /// returns: The initialized ticks in the range in ascending order, each with the cumulative
+    /// active liquidity
+    ///
+    pub fn liquidity_distribution(
+        &self,
+        tick_lower: i32,
+        tick_upper: i32,
+    ) -> Result<Vec<(i32, u128)>> {
+        assert!(
+            MIN_TICK <= tick_lower && tick_lower <= tick_upper && tick_upper <= MAX_TICK,
+            "TICKS"
+        );
+        // start just below the range so that a tick initialized at `tick_lower` is walked over
+        let mut current = (tick_lower - 1).max(MIN_TICK);
+        let mut liquidity = self.liquidity_at_tick(current)?;
+        let mut distribution = Vec::new();
+        loop {
+            let (next, initialized) = self
+                .tick_data_provider
+                .next_initialized_tick_within_one_word(current, false, self.tick_spacing())?;
+            if next > tick_upper {
+                break;
+            }
+            if initialized {
+                let liquidity_net = self.tick_data_provider.get_tick(next)?.liquidity_net;
+                liquidity = add_delta(liquidity, liquidity_net)?;
+                if next >= tick_lower {
+                    distribution.push((next, liquidity));
+                }
+            }
+            if next == tick_upper {
+                break;
+            }
+            current = next;
+        }
+        Ok(distribution)
+    }
+}
+
+/// Whether a protocol fee denominator is accepted by the pool contract's `setFeeProtocol`
+const fn is_valid_fee_protocol(fee_protocol: u8) -> bool {
+    fee_protocol == 0 || (fee_protocol >= 4 && fee_protocol <= 10)
+}
+
+/// Converts the magnitude of an amount to the amount specified of a swap, erroring instead of
+/// wrapping to an amount of the opposite sign, which would flip the swap between exact input and
+/// exact output
+fn amount_specified(amount: &BigInt) -> Result<I256, Error> {
+    U256::try_from_be_slice(&amount.to_bytes_be().1)
+        .and_then(|amount| I256::try_from(amount).ok())
+        .ok_or(Error::AmountOverflow)
+}
+
+/// Returns true if the error of simulating a swap against a pool only rules out that pool, so that
+/// the path finders can skip it like the TypeScript SDK does
+pub(crate) fn is_skippable_pool_error(err: &anyhow::Error) -> bool {
+    matches!(
+        err.downcast_ref(),
+        Some(Error::InsufficientLiquidity | Error::NoTickDataProvider)
+    )
+}
+
+/// Returns the pool that gives the largest output for the given input amount among pools of the
+/// same token pair, e.g. the same pair at different fee tiers
+///
+/// # Arguments
+///
+/// * `pools`: The candidate pools, which must all be for the same token pair
+/// * `amount_in`: The input amount to simulate in each pool
+///
+/// Pools that cannot fill the swap, e.g. without tick data, are skipped, erroring only if none is
+/// left. Other simulation errors are returned.
+///
+/// returns: Result<&Pool>
+///
+pub fn best_pool_for_amount<'a>(
+    pools: &'a [Pool],
+    amount_in: &CurrencyAmount<Token>,
+) -> Result<&'a Pool> {
+    let Some(first) = pools.first() else {
+        return Err(Error::IsZero.into());
+    };
+    if let Some(i) = pools
+        .iter()
+        .position(|pool| pool.token0 != first.token0 || pool.token1 != first.token1)
+    {
+        return Err(Error::PairMismatch(i).into());
+    }
+    let mut best: Option<(&Pool, BigInt)> = None;
+    let mut last_err = None;
+    for pool in pools {
+        let output = match pool.get_output_amount(amount_in.clone(), None) {
+            Ok((output, _)) => output.quotient(),
+            Err(err) if is_skippable_pool_error(&err) => {
+                last_err = Some(err);
+                continue;
+            }
+            Err(err) => return Err(err),
+        };
+        let is_better = match &best {
+            Some((_, best_output)) => output > *best_output,
+            None => true,
+        };
+        if is_better {
+            best = Some((pool, output));
+        }
+    }
+    match best {
+        Some((pool, _)) => Ok(pool),
+        None => Err(last_err.unwrap()),
+    }
+}
+
+/// Values an amount of a token in the quote token, e.g. USDC, at the mid price of a reference pool
+/// of the pair
+///
+/// # Arguments
+///
+/// * `amount`: The amount to value
+/// * `reference_pool`: The pool of the token of the amount and the quote token
+/// * `quote_token`: The token to value the amount in
+///
+/// returns: Result<CurrencyAmount<Token>>
+///
+pub fn token_value_in(
+    amount: &CurrencyAmount<Token>,
+    reference_pool: &Pool,
+    quote_token: &Token,
+) -> Result<CurrencyAmount<Token>> {
+    if amount.meta.currency.equals(quote_token) {
+        return Ok(amount.clone());
+    }
+    if !reference_pool.involves_token(quote_token)
+        || !reference_pool.involves_token(&amount.meta.currency)
+    {
+        return Err(Error::InvolvesToken.into());
+    }
+    Ok(reference_pool
+        .clone()
+        .price_of(&amount.meta.currency)
+        .quote(amount.clone())?)
 }
 
 //Implement debug for Pool
@@ -382,6 +1294,8 @@ impl fmt::Debug for Pool {
             .field("sqrt_ratio_x96", &self.sqrt_ratio_x96)
             .field("liquidity", &self.liquidity)
             .field("tick_current", &self.tick_current)
+            .field("fee_protocol", &self.fee_protocol)
+            .field("has_ticks", &self.tick_data_provider.has_ticks())
             .finish()
     }
 }
@@ -395,6 +1309,7 @@ impl PartialEq for Pool {
             && self.sqrt_ratio_x96 == other.sqrt_ratio_x96
             && self.liquidity == other.liquidity
             && self.tick_current == other.tick_current
+            && self.fee_protocol == other.fee_protocol
     }
 }
 
@@ -428,10 +1343,9 @@ mod tests {
         use super::*;
 
         #[test]
-        #[should_panic(expected = "CHAIN_IDS")]
         fn cannot_be_used_for_tokens_on_different_chains() {
             let weth9 = WETH9::default().get(3).unwrap().clone();
-            Pool::new(
+            let err = Pool::new(
                 USDC.clone(),
                 weth9.clone(),
                 FeeAmount::MEDIUM,
@@ -439,13 +1353,16 @@ mod tests {
                 0,
                 None,
             )
-            .expect("CHAIN_IDS");
+            .unwrap_err();
+            assert!(matches!(
+                err.downcast_ref(),
+                Some(Error::ChainIdIsDifferent)
+            ));
         }
 
         #[test]
-        #[should_panic(expected = "ADDRESSES")]
         fn cannot_be_given_two_of_the_same_token() {
-            Pool::new(
+            let err = Pool::new(
                 USDC.clone(),
                 USDC.clone(),
                 FeeAmount::MEDIUM,
@@ -453,7 +1370,25 @@ mod tests {
                 0,
                 None,
             )
-            .expect("ADDRESSES");
+            .unwrap_err();
+            assert!(matches!(err.downcast_ref(), Some(Error::IdenticalTokens)));
+        }
+
+        #[test]
+        fn cannot_be_given_an_invalid_custom_fee() {
+            let err = Pool::new(
+                USDC.clone(),
+                DAI.clone(),
+                FeeAmount::Custom(1 << 24, 0),
+                ONE_ETHER,
+                0,
+                None,
+            )
+            .unwrap_err();
+            assert!(matches!(
+                err.downcast_ref(),
+                Some(Error::InvalidFeeAmount(..))
+            ));
         }
 
         #[test]
@@ -503,44 +1438,421 @@ mod tests {
             let weth9 = WETH9::default().get(1).unwrap().clone();
             Pool::new(
                 USDC.clone(),
-                weth9.clone(),
-                FeeAmount::HIGH,
-                ONE_ETHER,
+                weth9.clone(),
+                FeeAmount::HIGH,
+                ONE_ETHER,
+                0,
+                None,
+            )
+            .unwrap();
+        }
+
+        #[test]
+        fn works_at_the_sqrt_ratio_bounds() -> Result<()> {
+            let pool = Pool::new(
+                USDC.clone(),
+                DAI.clone(),
+                FeeAmount::MEDIUM,
+                MIN_SQRT_RATIO,
+                0,
+                None,
+            )?;
+            assert_eq!(pool.tick_current, MIN_TICK);
+            assert_eq!(get_sqrt_ratio_at_tick(pool.tick_current)?, MIN_SQRT_RATIO);
+
+            // the upper bound is exclusive, as in the pool contract
+            let pool = Pool::new(
+                USDC.clone(),
+                DAI.clone(),
+                FeeAmount::MEDIUM,
+                MAX_SQRT_RATIO - U256::from(1),
+                0,
+                None,
+            )?;
+            assert_eq!(pool.tick_current, MAX_TICK - 1);
+            assert!(get_sqrt_ratio_at_tick(pool.tick_current)? < pool.sqrt_ratio_x96);
+            assert_eq!(get_sqrt_ratio_at_tick(MAX_TICK)?, MAX_SQRT_RATIO);
+
+            let err = Pool::new(
+                USDC.clone(),
+                DAI.clone(),
+                FeeAmount::MEDIUM,
+                MAX_SQRT_RATIO,
+                0,
+                None,
+            )
+            .unwrap_err();
+            assert!(matches!(
+                err.downcast_ref(),
+                Some(uniswap_v3_math::error::UniswapV3MathError::R)
+            ));
+            Ok(())
+        }
+
+        #[test]
+        fn from_subgraph_json_builds_pool_with_ticks() -> Result<()> {
+            let value = serde_json::json!({
+                "id": "0x6c6bc977e13df9b0de53b251522280bb72383700",
+                "feeTier": "500",
+                "sqrtPrice": "79228162514264337593543950336",
+                "liquidity": "3000000",
+                "tick": "0",
+                "ticks": [
+                    { "tickIdx": "100", "liquidityGross": "2000000", "liquidityNet": "-2000000" },
+                    { "tickIdx": "-887270", "liquidityGross": "1000000", "liquidityNet": "1000000" },
+                    { "tickIdx": "-100", "liquidityGross": "2000000", "liquidityNet": "2000000" },
+                    { "tickIdx": "887270", "liquidityGross": "1000000", "liquidityNet": "-1000000" }
+                ]
+            });
+            let pool = Pool::from_subgraph_json(&value, DAI.clone(), USDC.clone())?;
+            assert_eq!(pool.token0, DAI.clone());
+            assert_eq!(pool.token1, USDC.clone());
+            assert_eq!(pool.fee, FeeAmount::LOW);
+            assert_eq!(pool.sqrt_ratio_x96, encode_sqrt_ratio_x96(1, 1));
+            assert_eq!(pool.liquidity, 3000000);
+            assert_eq!(pool.tick_current, 0);
+            assert_eq!(
+                pool.tick_data_provider.get_tick(-100)?.liquidity_net,
+                2000000
+            );
+            let ticks = value["ticks"].as_array().unwrap();
+            assert_eq!(ticks.len(), 4);
+            for tick in ticks {
+                let index = tick["tickIdx"].as_str().unwrap().parse()?;
+                assert_eq!(pool.tick_data_provider.get_tick(index)?.index, index);
+            }
+            Ok(())
+        }
+
+        #[test]
+        fn from_subgraph_json_accepts_the_tick_below_a_crossed_boundary() -> Result<()> {
+            let value = serde_json::json!({
+                "feeTier": "500",
+                "sqrtPrice": get_sqrt_ratio_at_tick(10)?.to_string(),
+                "liquidity": "0",
+                "tick": "9",
+                "ticks": []
+            });
+            let pool = Pool::from_subgraph_json(&value, DAI.clone(), USDC.clone())?;
+            assert_eq!(pool.tick_current, 9);
+            let value = serde_json::json!({
+                "feeTier": "500",
+                "sqrtPrice": get_sqrt_ratio_at_tick(10)?.to_string(),
+                "liquidity": "0",
+                "tick": "11",
+                "ticks": []
+            });
+            let err = Pool::from_subgraph_json(&value, DAI.clone(), USDC.clone()).unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                Error::InvalidSubgraphData("tick".to_string()).to_string()
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn from_subgraph_json_rejects_invalid_ticks() {
+            let pool = |ticks: serde_json::Value| {
+                Pool::from_subgraph_json(
+                    &serde_json::json!({
+                        "feeTier": "500",
+                        "sqrtPrice": "79228162514264337593543950336",
+                        "liquidity": "0",
+                        "tick": "0",
+                        "ticks": ticks
+                    }),
+                    DAI.clone(),
+                    USDC.clone(),
+                )
+            };
+            for ticks in [
+                // a partial page whose net liquidity does not sum to zero
+                serde_json::json!([
+                    { "tickIdx": "-100", "liquidityGross": "1", "liquidityNet": "1" }
+                ]),
+                // not aligned to the tick spacing of 10
+                serde_json::json!([
+                    { "tickIdx": "-105", "liquidityGross": "1", "liquidityNet": "1" },
+                    { "tickIdx": "100", "liquidityGross": "1", "liquidityNet": "-1" }
+                ]),
+                // out of range
+                serde_json::json!([
+                    { "tickIdx": "-887280", "liquidityGross": "1", "liquidityNet": "1" },
+                    { "tickIdx": "100", "liquidityGross": "1", "liquidityNet": "-1" }
+                ]),
+            ] {
+                let err = pool(ticks).unwrap_err();
+                assert!(matches!(
+                    err.downcast_ref(),
+                    Some(Error::InvalidSubgraphData(field)) if field.starts_with("ticks")
+                ));
+            }
+        }
+
+        #[test]
+        fn from_subgraph_json_rejects_missing_fields() {
+            let value = serde_json::json!({
+                "feeTier": "500",
+                "liquidity": "0",
+                "tick": "0",
+                "ticks": []
+            });
+            let err = Pool::from_subgraph_json(&value, DAI.clone(), USDC.clone()).unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                Error::InvalidSubgraphData("sqrtPrice".to_string()).to_string()
+            );
+        }
+    }
+
+    #[test]
+    fn get_address_matches_an_example() {
+        let result = Pool::get_address(&USDC, &DAI, FeeAmount::LOW, None, None);
+        assert_eq!(result, address!("6c6Bc977E13Df9b0de53b251522280BB72383700"));
+    }
+
+    #[test]
+    fn address_is_computed_once() {
+        let pool = Pool::new(
+            USDC.clone(),
+            DAI.clone(),
+            FeeAmount::LOW,
+            encode_sqrt_ratio_x96(1, 1),
+            0,
+            None,
+        )
+        .unwrap();
+        let before = ADDRESS_COMPUTATIONS.with(|count| count.get());
+        assert_eq!(
+            pool.address(),
+            Pool::get_address(&USDC, &DAI, FeeAmount::LOW, None, None)
+        );
+        assert_eq!(pool.address(), pool.address());
+        assert_eq!(ADDRESS_COMPUTATIONS.with(|count| count.get()), before);
+    }
+
+    #[test]
+    fn address_follows_changed_identity_fields() {
+        let mut pool = Pool::new(
+            USDC.clone(),
+            DAI.clone(),
+            FeeAmount::LOW,
+            encode_sqrt_ratio_x96(1, 1),
+            0,
+            None,
+        )
+        .unwrap();
+        pool.fee = FeeAmount::MEDIUM;
+        assert_eq!(
+            pool.address(),
+            Pool::get_address(&USDC, &DAI, FeeAmount::MEDIUM, None, None)
+        );
+    }
+
+    #[test]
+    fn token0_always_is_the_token_that_sorts_before() {
+        let pool = Pool::new(
+            USDC.clone(),
+            DAI.clone(),
+            FeeAmount::LOW,
+            encode_sqrt_ratio_x96(1, 1),
+            0,
+            None,
+        )
+        .unwrap();
+        assert!(pool.token0.equals(&DAI.clone()));
+        let pool = Pool::new(
+            DAI.clone(),
+            USDC.clone(),
+            FeeAmount::LOW,
+            encode_sqrt_ratio_x96(1, 1),
+            0,
+            None,
+        )
+        .unwrap();
+        assert!(pool.token0.equals(&DAI.clone()));
+    }
+
+    #[test]
+    fn try_new_from_u256_liquidity() -> Result<()> {
+        let pool = Pool::try_new_from_u256_liquidity(
+            USDC.clone(),
+            DAI.clone(),
+            FeeAmount::MEDIUM,
+            encode_sqrt_ratio_x96(1, 1),
+            U256::from(u128::MAX),
+            None,
+        )?;
+        assert_eq!(pool.liquidity, u128::MAX);
+
+        let err = Pool::try_new_from_u256_liquidity(
+            USDC.clone(),
+            DAI.clone(),
+            FeeAmount::MEDIUM,
+            encode_sqrt_ratio_x96(1, 1),
+            U256::from(u128::MAX) + U256::from(1),
+            None,
+        )
+        .unwrap_err();
+        assert!(matches!(err.downcast_ref(), Some(Error::LiquidityOverflow)));
+        Ok(())
+    }
+
+    #[test]
+    fn token_value_in_quote_token() -> Result<()> {
+        let weth = WETH9::default().get(1).unwrap().clone();
+        // 2000 USDC per WETH, USDC is token0
+        let pool = Pool::new(
+            USDC.clone(),
+            weth.clone(),
+            FeeAmount::MEDIUM,
+            encode_sqrt_ratio_x96(10_u128.pow(18), 2000 * 10_u128.pow(6)),
+            0,
+            None,
+        )?;
+        let amount = CurrencyAmount::from_raw_amount(weth.clone(), 2 * 10_u128.pow(18))?;
+        let value = token_value_in(&amount, &pool, &USDC)?;
+        assert_eq!(value.meta.currency, USDC.clone());
+        assert_eq!(value.to_fixed(2, Rounding::RoundHalfUp)?, "4000.00");
+
+        let value = token_value_in(&amount, &pool, &weth)?;
+        assert_eq!(value, amount);
+
+        let err = token_value_in(&amount, &pool, &DAI).unwrap_err();
+        assert!(matches!(err.downcast_ref(), Some(Error::InvolvesToken)));
+        Ok(())
+    }
+
+    #[test]
+    fn update_state_clears_the_cached_prices() -> Result<()> {
+        let pool_at = |sqrt_price| {
+            Pool::new(
+                USDC.clone(),
+                DAI.clone(),
+                FeeAmount::LOW,
+                sqrt_price,
                 0,
                 None,
             )
-            .unwrap();
-        }
+        };
+        let mut pool = pool_at(encode_sqrt_ratio_x96(1, 1))?;
+        let address = pool.address();
+        let price_before = pool.token0_price();
+
+        let sqrt_price = encode_sqrt_ratio_x96(101, 100);
+        let tick = get_tick_at_sqrt_ratio(sqrt_price)?;
+        pool.update_state(sqrt_price, 1000, tick)?;
+        assert_ne!(pool.token0_price(), price_before);
+        assert_eq!(pool.token0_price(), pool_at(sqrt_price)?.token0_price());
+        assert_eq!(pool.token1_price(), pool_at(sqrt_price)?.token1_price());
+        assert_eq!(pool.liquidity, 1000);
+        assert_eq!(pool.tick_current, tick);
+        assert_eq!(pool.address(), address);
+
+        // a price exactly at a tick may have been reached by crossing the tick downwards
+        pool.update_state(get_sqrt_ratio_at_tick(10)?, 1000, 9)?;
+        assert_eq!(pool.tick_current, 9);
+        assert!(matches!(
+            pool.update_state(sqrt_price, 1000, tick + 1),
+            Err(Error::InconsistentTick(t)) if t == tick + 1
+        ));
+        assert_eq!(pool.tick_current, 9);
+        Ok(())
     }
 
     #[test]
-    fn get_address_matches_an_example() {
-        let result = Pool::get_address(&USDC, &DAI, FeeAmount::LOW, None, None);
-        assert_eq!(result, address!("6c6Bc977E13Df9b0de53b251522280BB72383700"));
+    fn get_output_amount_from_currency_wraps_native_input() -> Result<()> {
+        let weth = WETH9::new().get(1).unwrap().clone();
+        let pool = full_range_pool(
+            &weth,
+            &USDC,
+            FeeAmount::MEDIUM,
+            ONE_ETHER.into_limbs()[0] as u128,
+        );
+        let native_in = CurrencyAmount::from_raw_amount(Ether::on_chain(1), 10000)?;
+        let (output, pool_after) = pool.get_output_amount_from_currency(&native_in, None)?;
+        let (expected, expected_pool_after) =
+            pool.get_output_amount(CurrencyAmount::from_raw_amount(weth, 10000)?, None)?;
+        assert_eq!(output.meta.currency, USDC.clone());
+        assert_eq!(output, expected);
+        assert_eq!(pool_after, expected_pool_after);
+        Ok(())
     }
 
     #[test]
-    fn token0_always_is_the_token_that_sorts_before() {
-        let pool = Pool::new(
+    fn from_slot0_takes_the_tick_of_slot0() -> Result<()> {
+        let sqrt_price = encode_sqrt_ratio_x96(101, 100);
+        let tick = get_tick_at_sqrt_ratio(sqrt_price)?;
+        let pool = Pool::from_slot0(
             USDC.clone(),
             DAI.clone(),
             FeeAmount::LOW,
-            encode_sqrt_ratio_x96(1, 1),
-            0,
+            (sqrt_price, tick),
+            1000,
+            None,
+        )?;
+        assert_eq!(pool.token0, DAI.clone());
+        assert_eq!(pool.token1, USDC.clone());
+        assert_eq!(pool.fee, FeeAmount::LOW);
+        assert_eq!(pool.sqrt_ratio_x96, sqrt_price);
+        assert_eq!(pool.tick_current, tick);
+        assert_eq!(pool.liquidity, 1000);
+
+        let boundary = get_sqrt_ratio_at_tick(10)?;
+        let pool = Pool::from_slot0(
+            USDC.clone(),
+            DAI.clone(),
+            FeeAmount::LOW,
+            (boundary, 9),
+            1000,
+            None,
+        )?;
+        assert_eq!(pool.tick_current, 9);
+        let err = Pool::from_slot0(
+            USDC.clone(),
+            DAI.clone(),
+            FeeAmount::LOW,
+            (sqrt_price, tick + 1),
+            1000,
             None,
         )
-        .unwrap();
-        assert!(pool.token0.equals(&DAI.clone()));
-        let pool = Pool::new(
+        .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref(),
+            Some(Error::InconsistentTick(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn token_order_does_not_change_the_pool() -> Result<()> {
+        let sqrt_ratio_x96 = encode_sqrt_ratio_x96(101e6 as u128, 100e18 as u128);
+        let mut pool = Pool::new(
+            USDC.clone(),
+            DAI.clone(),
+            FeeAmount::LOW,
+            sqrt_ratio_x96,
+            0,
+            None,
+        )?;
+        let mut sorted = Pool::new(
             DAI.clone(),
             USDC.clone(),
             FeeAmount::LOW,
-            encode_sqrt_ratio_x96(1, 1),
+            sqrt_ratio_x96,
             0,
             None,
-        )
-        .unwrap();
-        assert!(pool.token0.equals(&DAI.clone()));
+        )?;
+        assert_eq!(pool, sorted);
+        assert_eq!(pool.token0, DAI.clone());
+        assert_eq!(pool.token1, USDC.clone());
+        assert_eq!(
+            pool.token0_price()
+                .to_significant(5, Rounding::RoundHalfUp)?,
+            "1.01"
+        );
+        assert_eq!(pool.token0_price(), sorted.token0_price());
+        Ok(())
     }
 
     #[test]
@@ -703,23 +2015,35 @@ mod tests {
         use super::*;
 
         fn pool() -> Pool {
+            full_range_pool(
+                &USDC,
+                &DAI,
+                FeeAmount::LOW,
+                ONE_ETHER.into_limbs()[0] as u128,
+            )
+        }
+
+        fn multi_tick_pool() -> Pool {
+            let liquidity = ONE_ETHER.into_limbs()[0] as u128;
             Pool::new(
                 USDC.clone(),
                 DAI.clone(),
                 FeeAmount::LOW,
                 encode_sqrt_ratio_x96(1, 1),
-                ONE_ETHER.into_limbs()[0] as u128,
+                liquidity * 2,
                 Some(Arc::new(TickListDataProvider::new(
                     vec![
                         Tick::new(
                             nearest_usable_tick(MIN_TICK, FeeAmount::LOW.tick_spacing()),
-                            ONE_ETHER.into_limbs()[0] as u128,
-                            ONE_ETHER.into_limbs()[0] as i128,
+                            liquidity,
+                            liquidity as i128,
                         ),
+                        Tick::new(-100, liquidity, liquidity as i128),
+                        Tick::new(100, liquidity, -(liquidity as i128)),
                         Tick::new(
                             nearest_usable_tick(MAX_TICK, FeeAmount::LOW.tick_spacing()),
-                            ONE_ETHER.into_limbs()[0] as u128,
-                            -(ONE_ETHER.into_limbs()[0] as i128),
+                            liquidity,
+                            -(liquidity as i128),
                         ),
                     ],
                     FeeAmount::LOW.tick_spacing(),
@@ -728,6 +2052,530 @@ mod tests {
             .unwrap()
         }
 
+        #[test]
+        fn best_pool_for_amount_prefers_lower_fee() -> Result<()> {
+            let low = pool();
+            let medium = full_range_pool(
+                &USDC,
+                &DAI,
+                FeeAmount::MEDIUM,
+                ONE_ETHER.into_limbs()[0] as u128,
+            );
+            let pools = [medium, low];
+            let amount_in = CurrencyAmount::from_raw_amount(USDC.clone(), 1_000_000)?;
+            let best = best_pool_for_amount(&pools, &amount_in)?;
+            assert_eq!(best.fee, FeeAmount::LOW);
+
+            let no_ticks = Pool::new(
+                USDC.clone(),
+                DAI.clone(),
+                FeeAmount::LOWEST,
+                encode_sqrt_ratio_x96(1, 1),
+                ONE_ETHER.into_limbs()[0] as u128,
+                None,
+            )?;
+            let with_failing = [no_ticks.clone(), pools[0].clone()];
+            let best = best_pool_for_amount(&with_failing, &amount_in)?;
+            assert_eq!(best.fee, FeeAmount::MEDIUM);
+            assert!(matches!(
+                best_pool_for_amount(&[no_ticks], &amount_in)
+                    .unwrap_err()
+                    .downcast_ref(),
+                Some(Error::NoTickDataProvider)
+            ));
+
+            // errors other than an unfillable swap are not skipped
+            let liquidity = ONE_ETHER.into_limbs()[0] as u128;
+            let inconsistent = Pool::new(
+                USDC.clone(),
+                DAI.clone(),
+                FeeAmount::LOW,
+                encode_sqrt_ratio_x96(1, 1),
+                0,
+                Some(Arc::new(TickListDataProvider::new(
+                    vec![
+                        Tick::new(-100, liquidity, liquidity as i128),
+                        Tick::new(100, liquidity, -(liquidity as i128)),
+                    ],
+                    FeeAmount::LOW.tick_spacing(),
+                ))),
+            )?;
+            assert!(matches!(
+                best_pool_for_amount(&[inconsistent, pools[1].clone()], &amount_in)
+                    .unwrap_err()
+                    .downcast_ref(),
+                Some(Error::LiquidityUnderflow)
+            ));
+
+            let usdc_weth = Pool::new(
+                USDC.clone(),
+                WETH9::default().get(1).unwrap().clone(),
+                FeeAmount::LOW,
+                encode_sqrt_ratio_x96(1, 1),
+                0,
+                None,
+            )?;
+            let mismatched = [pools[1].clone(), usdc_weth];
+            assert!(matches!(
+                best_pool_for_amount(&mismatched, &amount_in)
+                    .unwrap_err()
+                    .downcast_ref(),
+                Some(Error::PairMismatch(1))
+            ));
+            Ok(())
+        }
+
+        #[test]
+        fn liquidity_at_tick_applies_net_liquidity() -> Result<()> {
+            let pool = multi_tick_pool();
+            let liquidity = pool.liquidity / 2;
+            assert_eq!(pool.liquidity_at_tick(pool.tick_current)?, pool.liquidity);
+            assert_eq!(pool.liquidity_at_tick(99)?, 2 * liquidity);
+            assert_eq!(pool.liquidity_at_tick(100)?, liquidity);
+            assert_eq!(pool.liquidity_at_tick(5000)?, liquidity);
+            assert_eq!(pool.liquidity_at_tick(-100)?, 2 * liquidity);
+            assert_eq!(pool.liquidity_at_tick(-101)?, liquidity);
+            assert_eq!(pool.liquidity_at_tick(-5000)?, liquidity);
+            Ok(())
+        }
+
+        #[test]
+        fn liquidity_distribution_accumulates_net_liquidity() -> Result<()> {
+            let pool = multi_tick_pool();
+            let liquidity = pool.liquidity / 2;
+            let min_tick = nearest_usable_tick(MIN_TICK, pool.tick_spacing());
+            let max_tick = nearest_usable_tick(MAX_TICK, pool.tick_spacing());
+            assert_eq!(
+                pool.liquidity_distribution(MIN_TICK, MAX_TICK)?,
+                vec![
+                    (min_tick, liquidity),
+                    (-100, 2 * liquidity),
+                    (100, liquidity),
+                    (max_tick, 0)
+                ]
+            );
+            assert_eq!(
+                pool.liquidity_distribution(-100, 100)?,
+                vec![(-100, 2 * liquidity), (100, liquidity)]
+            );
+            assert_eq!(
+                pool.liquidity_distribution(-99, 5000)?,
+                vec![(100, liquidity)]
+            );
+            assert!(pool.liquidity_distribution(-90, 90)?.is_empty());
+            Ok(())
+        }
+
+        #[test]
+        fn get_output_amount_detailed_reports_the_price_limit() -> Result<()> {
+            let pool = multi_tick_pool();
+            let input_amount =
+                CurrencyAmount::from_raw_amount(USDC.clone(), u256_to_big_int(ONE_ETHER))?;
+            // usdc is token1, so the price goes up
+            let limit = get_sqrt_ratio_at_tick(pool.tick_current + 10)?;
+            let limited = pool.get_output_amount_detailed(input_amount.clone(), Some(limit))?;
+            assert!(limited.limit_reached);
+            assert_eq!(limited.pool.sqrt_ratio_x96, limit);
+
+            let unlimited = pool.get_output_amount_detailed(input_amount.clone(), None)?;
+            assert!(!unlimited.limit_reached);
+            assert!(limited.output_amount.quotient() < unlimited.output_amount.quotient());
+            let (output_amount, _) = pool.get_output_amount(input_amount, None)?;
+            assert_eq!(unlimited.output_amount, output_amount);
+
+            // a limit beyond where the input runs out is not reached
+            let far_limit = get_sqrt_ratio_at_tick(pool.tick_current + 1000)?;
+            let small_input = CurrencyAmount::from_raw_amount(USDC.clone(), 100)?;
+            assert!(
+                !pool
+                    .get_output_amount_detailed(small_input, Some(far_limit))?
+                    .limit_reached
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn get_input_amount_errors_if_the_output_cannot_be_filled() -> Result<()> {
+            let pool = pool();
+            let (output_amount, _) = pool.get_output_amount(
+                CurrencyAmount::from_raw_amount(DAI.clone(), 10u128.pow(38))?,
+                None,
+            )?;
+            let too_much = CurrencyAmount::from_raw_amount(
+                USDC.clone(),
+                output_amount.quotient() + BigInt::from(1),
+            )?;
+            let err = pool.get_input_amount(too_much.clone(), None).unwrap_err();
+            assert!(matches!(
+                err.downcast_ref(),
+                Some(Error::InsufficientLiquidity)
+            ));
+            // a partial fill up to an explicit price limit is allowed
+            let limit = get_sqrt_ratio_at_tick(pool.tick_current - 10)?;
+            pool.get_input_amount(too_much, Some(limit))?;
+            Ok(())
+        }
+
+        #[test]
+        fn amounts_above_i256_max_error_instead_of_wrapping() -> Result<()> {
+            let pool = pool();
+            let too_large = U256::from(1) << 255;
+            let err = pool
+                .get_output_amount_raw(too_large, true, None)
+                .unwrap_err();
+            assert!(matches!(err.downcast_ref(), Some(Error::AmountOverflow)));
+            let err = pool
+                .get_output_amount(
+                    CurrencyAmount::from_raw_amount(DAI.clone(), u256_to_big_int(too_large))?,
+                    None,
+                )
+                .unwrap_err();
+            assert!(matches!(err.downcast_ref(), Some(Error::AmountOverflow)));
+            let err = pool
+                .get_input_amount(
+                    CurrencyAmount::from_raw_amount(USDC.clone(), u256_to_big_int(too_large))?,
+                    None,
+                )
+                .unwrap_err();
+            assert!(matches!(err.downcast_ref(), Some(Error::AmountOverflow)));
+            Ok(())
+        }
+
+        #[test]
+        fn inconsistent_liquidity_net_errors_instead_of_wrapping() -> Result<()> {
+            let liquidity = ONE_ETHER.into_limbs()[0] as u128;
+            // the in-range liquidity should be `liquidity`, so crossing tick 100 underflows
+            let pool = Pool::new(
+                USDC.clone(),
+                DAI.clone(),
+                FeeAmount::LOW,
+                encode_sqrt_ratio_x96(1, 1),
+                0,
+                Some(Arc::new(TickListDataProvider::new(
+                    vec![
+                        Tick::new(-100, liquidity, liquidity as i128),
+                        Tick::new(100, liquidity, -(liquidity as i128)),
+                    ],
+                    FeeAmount::LOW.tick_spacing(),
+                ))),
+            )?;
+            let err = pool
+                .get_output_amount(CurrencyAmount::from_raw_amount(USDC.clone(), 100)?, None)
+                .unwrap_err();
+            assert!(matches!(
+                err.downcast_ref(),
+                Some(Error::LiquidityUnderflow)
+            ));
+            Ok(())
+        }
+
+        #[test]
+        fn zero_amounts_return_zero() -> Result<()> {
+            let pool = pool();
+            let (output, pool_after) =
+                pool.get_output_amount(CurrencyAmount::from_raw_amount(USDC.clone(), 0)?, None)?;
+            assert_eq!(output.meta.currency, DAI.clone());
+            assert!(output.quotient().is_zero());
+            assert_eq!(pool_after, pool);
+
+            let (input, pool_after) =
+                pool.get_input_amount(CurrencyAmount::from_raw_amount(DAI.clone(), 0)?, None)?;
+            assert_eq!(input.meta.currency, USDC.clone());
+            assert!(input.quotient().is_zero());
+            assert_eq!(pool_after, pool);
+            Ok(())
+        }
+
+        #[test]
+        fn get_output_amount_with_context_reuses_the_context() -> Result<()> {
+            let pool = multi_tick_pool();
+            let mut ctx = SwapContext::default();
+            let mut buffer = None;
+            for amount in [10_u128.pow(18), 2 * 10_u128.pow(18), 100] {
+                let amount_in = CurrencyAmount::from_raw_amount(DAI.clone(), amount)?;
+                let output =
+                    pool.get_output_amount_with_context(amount_in.clone(), None, &mut ctx)?;
+                let (expected_output, expected_pool) = pool.get_output_amount(amount_in, None)?;
+                assert_eq!(output, expected_output);
+                let state = ctx.pool_state().unwrap();
+                assert_eq!(state.sqrt_ratio_x96, expected_pool.sqrt_ratio_x96);
+                assert_eq!(state.liquidity, expected_pool.liquidity);
+                assert_eq!(state.tick_current, expected_pool.tick_current);
+                if amount == 100 {
+                    assert!(ctx.crossed_ticks().is_empty());
+                } else {
+                    assert_eq!(ctx.crossed_ticks(), [-100]);
+                }
+                // the buffer allocated by the first swap is reused
+                let ptr = ctx.crossed_ticks.as_ptr();
+                assert_eq!(*buffer.get_or_insert(ptr), ptr);
+            }
+            Ok(())
+        }
+
+        #[test]
+        fn swaps_in_custom_fee_tier() -> Result<()> {
+            let fee = FeeAmount::Custom(250, 5);
+            let liquidity = ONE_ETHER.into_limbs()[0] as u128;
+            let custom = full_range_pool(&USDC, &DAI, fee, liquidity);
+            let amount_in = CurrencyAmount::from_raw_amount(USDC.clone(), 1_000_000)?;
+            let (custom_output, _) = custom.get_output_amount(amount_in.clone(), None)?;
+            let (low_output, _) = pool().get_output_amount(amount_in, None)?;
+            // the lower fee gives more output
+            assert!(custom_output.quotient() > low_output.quotient());
+            Ok(())
+        }
+
+        #[test]
+        fn unlimited_swap_consumes_all_liquidity() -> Result<()> {
+            let pool = pool();
+            let liquidity = ONE_ETHER.into_limbs()[0] as u128;
+            let (output, pool_after) = pool.get_output_amount(
+                CurrencyAmount::from_raw_amount(DAI.clone(), 10_u128.pow(38))?,
+                None,
+            )?;
+            assert_eq!(output.meta.currency, USDC.clone());
+            assert!(output.quotient() < BigInt::from(liquidity));
+            assert!(output.quotient() > BigInt::from(liquidity - liquidity / 1000));
+            assert_eq!(pool_after.sqrt_ratio_x96, MIN_SQRT_RATIO + U256::from(1));
+            assert_eq!(pool_after.liquidity, 0);
+
+            let (output, pool_after) = pool.get_output_amount(
+                CurrencyAmount::from_raw_amount(USDC.clone(), 10_u128.pow(38))?,
+                None,
+            )?;
+            assert_eq!(output.meta.currency, DAI.clone());
+            assert!(output.quotient() < BigInt::from(liquidity));
+            assert_eq!(pool_after.sqrt_ratio_x96, MAX_SQRT_RATIO - U256::from(1));
+            assert_eq!(pool_after.liquidity, 0);
+            Ok(())
+        }
+
+        #[test]
+        fn swapped_pool_shares_tick_data_provider() -> Result<()> {
+            let pool = pool();
+            let strong_count = Arc::strong_count(&pool.tick_data_provider);
+            let (_, pool_after) =
+                pool.get_output_amount(CurrencyAmount::from_raw_amount(USDC.clone(), 100)?, None)?;
+            assert!(Arc::ptr_eq(
+                &pool.tick_data_provider,
+                &pool_after.tick_data_provider
+            ));
+            assert_eq!(
+                Arc::strong_count(&pool.tick_data_provider),
+                strong_count + 1
+            );
+
+            let (_, pool_after) =
+                pool.get_input_amount(CurrencyAmount::from_raw_amount(DAI.clone(), 100)?, None)?;
+            assert!(Arc::ptr_eq(
+                &pool.tick_data_provider,
+                &pool_after.tick_data_provider
+            ));
+            Ok(())
+        }
+
+        #[test]
+        fn get_output_amount_without_tick_data_provider() {
+            let pool = Pool::new(
+                USDC.clone(),
+                DAI.clone(),
+                FeeAmount::LOW,
+                encode_sqrt_ratio_x96(1, 1),
+                ONE_ETHER.into_limbs()[0] as u128,
+                None,
+            )
+            .unwrap();
+            let amount_in = CurrencyAmount::from_raw_amount(USDC.clone(), 100).unwrap();
+            let err = pool.get_output_amount(amount_in, None).unwrap_err();
+            assert!(matches!(
+                err.downcast_ref(),
+                Some(Error::NoTickDataProvider)
+            ));
+        }
+
+        #[test]
+        fn debug_omits_tick_data() {
+            let debug = format!("{:?}", multi_tick_pool());
+            assert!(debug.contains("fee: LOW"));
+            assert!(debug.contains("tick_current: 0"));
+            assert!(debug.contains("has_ticks: true"));
+            assert!(!debug.contains("Tick {"));
+
+            let pool = Pool::new(
+                USDC.clone(),
+                DAI.clone(),
+                FeeAmount::LOW,
+                encode_sqrt_ratio_x96(1, 1),
+                0,
+                None,
+            )
+            .unwrap();
+            assert!(format!("{:?}", pool).contains("has_ticks: false"));
+        }
+
+        #[test]
+        fn invalid_protocol_fees_are_rejected() -> Result<()> {
+            let mut pool = pool();
+            for fee_protocol in [1, 3, 11] {
+                assert!(matches!(
+                    pool.set_fee_protocol(4, fee_protocol),
+                    Err(Error::InvalidFeeProtocol(f)) if f == fee_protocol
+                ));
+            }
+            assert_eq!(pool.fee_protocol, (0, 0));
+            pool.set_fee_protocol(0, 10)?;
+            assert_eq!(pool.fee_protocol, (0, 10));
+
+            pool.fee_protocol = (0, 2);
+            let amount_in = CurrencyAmount::from_raw_amount(USDC.clone(), 100)?;
+            // only the protocol fee of the input token is used by the swap
+            let err = pool.get_output_amount(amount_in, None).unwrap_err();
+            assert!(matches!(
+                err.downcast_ref(),
+                Some(Error::InvalidFeeProtocol(2))
+            ));
+            pool.get_output_amount(CurrencyAmount::from_raw_amount(DAI.clone(), 100)?, None)?;
+            Ok(())
+        }
+
+        #[test]
+        fn protocol_fee_does_not_change_output_amount() -> Result<()> {
+            let pool = pool();
+            let mut pool_with_fee = pool.clone();
+            pool_with_fee.set_fee_protocol(4, 4)?;
+            let amount_in = CurrencyAmount::from_raw_amount(USDC.clone(), 100)?;
+
+            let (output, _) = pool.get_output_amount(amount_in.clone(), None)?;
+            let (output_with_fee, pool_after) =
+                pool_with_fee.get_output_amount(amount_in.clone(), None)?;
+            assert_eq!(output_with_fee, output);
+            assert_eq!(pool_after.fee_protocol, (4, 4));
+
+            let amount_specified = I256::from_raw(ONE_ETHER);
+            let trace = pool.swap_with_trace(true, amount_specified, None)?;
+            let trace_with_fee = pool_with_fee.swap_with_trace(true, amount_specified, None)?;
+            assert_eq!(trace.protocol_fee, U256::ZERO);
+            assert_eq!(trace_with_fee.amount1, trace.amount1);
+            // a quarter of the 0.05% fee on 1e18, rounded down in each swap step
+            assert_eq!(
+                trace_with_fee.protocol_fee,
+                U256::from(124_999_999_999_999u64)
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn amount_to_reach_tick_moves_price_one_tick() -> Result<()> {
+            let pool = pool();
+            for target_tick in [1, -1] {
+                let (amount_in, amount_out) = pool.amount_to_reach_tick(target_tick)?;
+                assert!(amount_in.quotient() > BigInt::zero());
+                assert!(amount_out.quotient() > BigInt::zero());
+
+                let sqrt_price_target_x96 = get_sqrt_ratio_at_tick(target_tick)?;
+                let (output, pool_after) =
+                    pool.get_output_amount(amount_in, Some(sqrt_price_target_x96))?;
+                assert_eq!(output, amount_out);
+                assert_eq!(
+                    pool_after.sqrt_ratio_x96,
+                    get_sqrt_ratio_at_tick(target_tick)?
+                );
+            }
+            // 1e18 * (sqrt(1.0001) - 1) plus the 0.05% fee
+            let (amount_in, _) = pool.amount_to_reach_tick(1)?;
+            assert_eq!(amount_in.meta.currency, USDC.clone());
+            assert_eq!(amount_in.quotient(), BigInt::from(50023761943469u64));
+            Ok(())
+        }
+
+        #[test]
+        fn pool_after_stopping_on_a_crossed_tick_does_not_cross_it_again() -> Result<()> {
+            let pool = multi_tick_pool();
+            let liquidity = ONE_ETHER.into_limbs()[0] as u128;
+            let (amount_in, _) = pool.amount_to_reach_tick(-100)?;
+            let (_, pool_after) =
+                pool.get_output_amount(amount_in, Some(get_sqrt_ratio_at_tick(-100)?))?;
+            assert_eq!(pool_after.sqrt_ratio_x96, get_sqrt_ratio_at_tick(-100)?);
+            assert_eq!(pool_after.tick_current, -101);
+            assert_eq!(pool_after.liquidity, liquidity);
+
+            let (_, pool_after) = pool_after
+                .get_output_amount(CurrencyAmount::from_raw_amount(DAI.clone(), 1000)?, None)?;
+            assert_eq!(pool_after.liquidity, liquidity);
+            Ok(())
+        }
+
+        #[test]
+        fn amount_to_reach_tick_rejects_invalid_targets() {
+            let pool = pool();
+            let err = pool.amount_to_reach_tick(0).unwrap_err();
+            assert!(matches!(
+                err.downcast_ref(),
+                Some(Error::TickAtCurrentPrice(0))
+            ));
+            for target_tick in [MIN_TICK, MAX_TICK, MAX_TICK + 1] {
+                let err = pool.amount_to_reach_tick(target_tick).unwrap_err();
+                assert!(matches!(
+                    err.downcast_ref(),
+                    Some(Error::TickOutOfRange(tick)) if *tick == target_tick
+                ));
+            }
+        }
+
+        #[test]
+        fn swap_exact_input() -> Result<()> {
+            let pool = pool();
+            let amount_specified = I256::from_raw(U256::from(100));
+            let (amount0, amount1, sqrt_price_after, _, tick_after) =
+                pool.swap(true, amount_specified, None)?;
+            assert_eq!(amount0, amount_specified);
+            assert_eq!(amount1, I256::from_raw(U256::from(98)).neg());
+            assert!(sqrt_price_after < pool.sqrt_ratio_x96);
+            assert_eq!(tick_after, -1);
+            Ok(())
+        }
+
+        #[test]
+        fn swap_exact_output() -> Result<()> {
+            let pool = pool();
+            let amount_specified = I256::from_raw(U256::from(98)).neg();
+            let (amount0, amount1, sqrt_price_after, liquidity_after, _) =
+                pool.swap(true, amount_specified, None)?;
+            assert_eq!(amount0, I256::from_raw(U256::from(100)));
+            assert_eq!(amount1, amount_specified);
+            assert!(sqrt_price_after < pool.sqrt_ratio_x96);
+            assert_eq!(liquidity_after, pool.liquidity);
+            Ok(())
+        }
+
+        #[test]
+        fn swap_with_trace_reports_crossed_ticks() -> Result<()> {
+            let pool = multi_tick_pool();
+            let amount_specified = I256::from_raw(ONE_ETHER);
+
+            let trace =
+                pool.swap_with_trace(true, amount_specified, Some(get_sqrt_ratio_at_tick(-150)?))?;
+            assert_eq!(trace.crossed_ticks, vec![-100]);
+            assert_eq!(trace.tick_after, -150);
+            assert_eq!(trace.liquidity_after, pool.liquidity / 2);
+            assert!(trace.amount0 > I256::ZERO && trace.amount0 < amount_specified);
+            assert!(trace.amount1 < I256::ZERO);
+
+            let trace =
+                pool.swap_with_trace(false, amount_specified, Some(get_sqrt_ratio_at_tick(150)?))?;
+            assert_eq!(trace.crossed_ticks, vec![100]);
+            assert_eq!(trace.tick_after, 150);
+            assert_eq!(trace.liquidity_after, pool.liquidity / 2);
+            assert!(trace.amount0 < I256::ZERO);
+            assert!(trace.amount1 > I256::ZERO && trace.amount1 < amount_specified);
+
+            let trace = pool.swap_with_trace(true, I256::from_raw(U256::from(100)), None)?;
+            assert!(trace.crossed_ticks.is_empty());
+            assert_eq!(trace.amount0, I256::from_raw(U256::from(100)));
+            Ok(())
+        }
+
         #[test]
         fn get_output_amount_usdc_to_dai() -> Result<()> {
             let (output_amount, _) = pool()
@@ -746,6 +2594,47 @@ mod tests {
             Ok(())
         }
 
+        #[test]
+        fn get_output_amount_raw_matches_currency_amount_version() -> Result<()> {
+            let pool = pool();
+            let (output_amount, new_pool) =
+                pool.get_output_amount(CurrencyAmount::from_raw_amount(USDC.clone(), 100)?, None)?;
+            let (raw_output_amount, state) =
+                pool.get_output_amount_raw(U256::from(100), false, None)?;
+            assert_eq!(u256_to_big_int(raw_output_amount), output_amount.quotient());
+            assert_eq!(state.sqrt_ratio_x96, new_pool.sqrt_ratio_x96);
+            assert_eq!(state.liquidity, new_pool.liquidity);
+            assert_eq!(state.tick_current, new_pool.tick_current);
+            Ok(())
+        }
+
+        #[test]
+        fn max_amount_in_for_price_impact_stays_under_threshold() -> Result<()> {
+            let pool = pool();
+            let max_impact = Percent::new(1, 100);
+            let amount_in = pool.max_amount_in_for_price_impact(&USDC, max_impact.clone())?;
+            assert!(amount_in.quotient() > BigInt::zero());
+            assert!(pool.price_impact_of(&USDC, amount_in.quotient())? < max_impact);
+            assert!(pool.price_impact_of(&USDC, amount_in.quotient() + 1)? >= max_impact);
+            Ok(())
+        }
+
+        #[test]
+        fn quote_single_matches_simulation() -> Result<()> {
+            let pool = pool();
+            let amount_in = CurrencyAmount::from_raw_amount(USDC.clone(), 100)?;
+            assert_eq!(
+                pool.quote_exact_input_single(amount_in.clone(), None)?,
+                pool.get_output_amount(amount_in, None)?.0
+            );
+            let amount_out = CurrencyAmount::from_raw_amount(DAI.clone(), 98)?;
+            assert_eq!(
+                pool.quote_exact_output_single(amount_out.clone(), None)?,
+                pool.get_input_amount(amount_out, None)?.0
+            );
+            Ok(())
+        }
+
         #[test]
         fn get_input_amount_usdc_to_dai() -> Result<()> {
             let (input_amount, _) =
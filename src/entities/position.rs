@@ -1,4 +1,4 @@
-use crate::prelude::*;
+use crate::{error::Error, prelude::*};
 use alloy_primitives::U256;
 use anyhow::Result;
 use uniswap_sdk_core::prelude::*;
@@ -72,6 +72,70 @@ impl Position {
         )
     }
 
+    /// Returns the share of the pool's in-range liquidity that this position provides, or zero if
+    /// the position is out of range
+    pub fn pool_liquidity_share(&self) -> Percent {
+        if self.pool.tick_current < self.tick_lower
+            || self.pool.tick_current >= self.tick_upper
+            || self.pool.liquidity == 0
+        {
+            return Percent::new(0, 1);
+        }
+        Percent::new(self.liquidity, self.pool.liquidity)
+    }
+
+    /// Returns the signed change in liquidity from this position to `other`, e.g. when moving
+    /// liquidity from one range to another of the same pool, or [`Error::LiquidityOverflow`] if
+    /// the change does not fit in an `i128`
+    pub fn liquidity_delta_to(&self, other: &Position) -> Result<i128> {
+        assert!(
+            self.pool.token0 == other.pool.token0
+                && self.pool.token1 == other.pool.token1
+                && self.pool.fee == other.pool.fee,
+            "POOL"
+        );
+        let delta = if other.liquidity >= self.liquidity {
+            i128::try_from(other.liquidity - self.liquidity).ok()
+        } else {
+            0_i128.checked_sub_unsigned(self.liquidity - other.liquidity)
+        };
+        Ok(delta.ok_or(Error::LiquidityOverflow)?)
+    }
+
+    /// Returns the fees that this position could collect, wrapped in the pool tokens
+    ///
+    /// # Arguments
+    ///
+    /// * `fee_growth_inside_0_x128`: The growth of the token0 fees per unit of liquidity inside the
+    ///   position's range since its fees were last collected
+    /// * `fee_growth_inside_1_x128`: The same for the token1 fees
+    ///
+    /// returns: The collectable amounts of token0 and token1
+    ///
+    pub fn collectable_fees(
+        &self,
+        fee_growth_inside_0_x128: U256,
+        fee_growth_inside_1_x128: U256,
+    ) -> Result<(CurrencyAmount<Token>, CurrencyAmount<Token>)> {
+        let (tokens_owed_0, tokens_owed_1) = get_tokens_owed(
+            U256::ZERO,
+            U256::ZERO,
+            self.liquidity,
+            fee_growth_inside_0_x128,
+            fee_growth_inside_1_x128,
+        );
+        Ok((
+            CurrencyAmount::from_raw_amount(
+                self.pool.token0.clone(),
+                u256_to_big_int(tokens_owed_0),
+            )?,
+            CurrencyAmount::from_raw_amount(
+                self.pool.token1.clone(),
+                u256_to_big_int(tokens_owed_1),
+            )?,
+        ))
+    }
+
     /// Returns the amount of token0 that this position's liquidity could be burned for at the current pool price
     pub fn amount0(&mut self) -> Result<&CurrencyAmount<Token>> {
         if self._token0_amount.is_none() {
@@ -287,6 +351,10 @@ impl Position {
 
     /// Returns the minimum amounts that must be sent in order to mint the amount of liquidity held by the position at
     /// the current price for the pool
+    ///
+    /// Both amounts are rounded up as in the `mint` of the pool contract, so that the pool never receives less than
+    /// the liquidity is worth. They are therefore one wei more than [`Position::amount0`] and [`Position::amount1`],
+    /// which round down, whenever the division is inexact.
     pub fn mint_amounts(&mut self) -> Result<MintAmounts> {
         if self._mint_amounts.is_none() {
             if self.pool.tick_current < self.tick_lower {
@@ -329,6 +397,18 @@ impl Position {
         Ok(self._mint_amounts.clone().unwrap())
     }
 
+    /// Returns the minimum amounts that must be sent in order to mint the amount of liquidity held
+    /// by the position at the current price for the pool, in the currencies of the pool tokens
+    pub fn mint_amounts_currency(
+        &mut self,
+    ) -> Result<(CurrencyAmount<Token>, CurrencyAmount<Token>)> {
+        let MintAmounts { amount0, amount1 } = self.mint_amounts()?;
+        Ok((
+            CurrencyAmount::from_raw_amount(self.pool.token0.clone(), u256_to_big_int(amount0))?,
+            CurrencyAmount::from_raw_amount(self.pool.token1.clone(), u256_to_big_int(amount1))?,
+        ))
+    }
+
     /// Computes the maximum amount of liquidity received for a given amount of token0, token1,
     /// and the prices at the tick boundaries.
     ///
@@ -340,7 +420,7 @@ impl Position {
     /// * `amount0`: token0 amount
     /// * `amount1`: token1 amount
     /// * `use_full_precision`: If false, liquidity will be maximized according to what the router can calculate,
-    /// not what core can theoretically support
+    ///   not what core can theoretically support
     ///
     /// returns: The position with the maximum amount of liquidity received
     ///
@@ -370,6 +450,45 @@ impl Position {
         ))
     }
 
+    /// Computes the position with the maximum amount of liquidity received for the given amounts
+    /// within a price range, widened to the nearest usable ticks so that the range brackets the
+    /// prices.
+    ///
+    /// # Arguments
+    ///
+    /// * `pool`: The pool for which the position should be created
+    /// * `price_lower`: The lower price of the range
+    /// * `price_upper`: The upper price of the range, in the same base and quote as `price_lower`
+    /// * `amount0`: token0 amount
+    /// * `amount1`: token1 amount
+    ///
+    /// returns: The position with the maximum amount of liquidity received
+    ///
+    pub fn from_price_range(
+        pool: Pool,
+        price_lower: Price<Token, Token>,
+        price_upper: Price<Token, Token>,
+        amount0: U256,
+        amount1: U256,
+    ) -> Result<Self> {
+        assert!(price_lower < price_upper, "PRICE_ORDER");
+        let tick_spacing = pool.tick_spacing();
+        let tick_a = price_to_closest_tick(price_lower)?;
+        let tick_b = price_to_closest_tick(price_upper)?;
+        // the price of the closest tick is at most the price, so round the larger tick up past it
+        let tick_lower = tick_a.min(tick_b).div_euclid(tick_spacing) * tick_spacing;
+        let tick_upper =
+            (tick_a.max(tick_b) + tick_spacing).div_euclid(tick_spacing) * tick_spacing;
+        Self::from_amounts(
+            pool,
+            tick_lower.max(nearest_usable_tick(MIN_TICK, tick_spacing)),
+            tick_upper.min(nearest_usable_tick(MAX_TICK, tick_spacing)),
+            amount0,
+            amount1,
+            true,
+        )
+    }
+
     /// Computes a position with the maximum amount of liquidity received for a given amount of token0,
     /// assuming an unlimited amount of token1
     ///
@@ -380,7 +499,7 @@ impl Position {
     /// * `tick_upper`: The upper tick
     /// * `amount0`: The desired amount of token0
     /// * `use_full_precision`: If true, liquidity will be maximized according to what the router can calculate,
-    /// not what core can theoretically support
+    ///   not what core can theoretically support
     ///
     /// returns: Position
     ///
@@ -472,6 +591,28 @@ mod tests {
         assert_eq!(position.liquidity, 1);
     }
 
+    #[test]
+    fn collectable_fees_wrap_the_tokens_owed() -> Result<()> {
+        let liquidity = 100e18 as u128;
+        let position = Position::new(dai_usdc_pool(), liquidity, -10, 10);
+        let (fee_growth_0, fee_growth_1) =
+            (Q128 * U256::from(3) / U256::from(7), Q128 * U256::from(2));
+        let (amount0, amount1) = position.collectable_fees(fee_growth_0, fee_growth_1)?;
+        let (tokens_owed_0, tokens_owed_1) = get_tokens_owed(
+            U256::ZERO,
+            U256::ZERO,
+            liquidity,
+            fee_growth_0,
+            fee_growth_1,
+        );
+        assert_eq!(amount0.meta.currency, position.pool.token0);
+        assert_eq!(amount1.meta.currency, position.pool.token1);
+        assert_eq!(amount0.quotient(), u256_to_big_int(tokens_owed_0));
+        assert_eq!(amount1.quotient(), u256_to_big_int(tokens_owed_1));
+        assert_eq!(amount1.quotient(), BigInt::from(2 * liquidity));
+        Ok(())
+    }
+
     #[test]
     fn can_use_min_and_max_ticks() {
         let position = Position::new(
@@ -529,6 +670,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn token0_price_bounds_bracket_the_current_price() {
+        let mut position = Position::new(
+            dai_usdc_pool(),
+            1,
+            nearest_usable_tick(*POOL_TICK_CURRENT, TICK_SPACING) - TICK_SPACING * 2,
+            nearest_usable_tick(*POOL_TICK_CURRENT, TICK_SPACING) + TICK_SPACING * 2,
+        );
+        let lower = position.token0_price_lower().unwrap();
+        let upper = position.token0_price_upper().unwrap();
+        let current = position.pool.token0_price();
+        assert!(lower.meta.base_currency.equals(&position.pool.token0));
+        assert!(upper.meta.quote_currency.equals(&position.pool.token1));
+        assert!(lower < current);
+        assert!(current < upper);
+    }
+
     #[test]
     fn amount0_is_correct_for_price_above() {
         let mut position = Position::new(
@@ -847,6 +1005,22 @@ mod tests {
         assert_eq!(amount1.to_string(), "54828800460");
     }
 
+    #[test]
+    fn mint_amounts_currency_wraps_mint_amounts_in_pool_tokens() {
+        let mut position = Position::new(
+            dai_usdc_pool(),
+            100e18 as u128,
+            nearest_usable_tick(*POOL_TICK_CURRENT, TICK_SPACING) - TICK_SPACING * 2,
+            nearest_usable_tick(*POOL_TICK_CURRENT, TICK_SPACING) + TICK_SPACING * 2,
+        );
+        let MintAmounts { amount0, amount1 } = position.mint_amounts().unwrap();
+        let (currency_amount0, currency_amount1) = position.mint_amounts_currency().unwrap();
+        assert_eq!(currency_amount0.meta.currency, position.pool.token0);
+        assert_eq!(currency_amount1.meta.currency, position.pool.token1);
+        assert_eq!(currency_amount0.quotient(), u256_to_big_int(amount0));
+        assert_eq!(currency_amount1.quotient(), u256_to_big_int(amount1));
+    }
+
     #[test]
     fn mint_amounts_is_correct_for_pool_at_min_price() {
         let mut position = Position::new(
@@ -933,4 +1107,104 @@ mod tests {
         assert_eq!(amount0.to_string(), "120054069145287995769397");
         assert_eq!(amount1.to_string(), "79831926243");
     }
+
+    #[test]
+    fn mint_amounts_round_up_the_position_amounts() {
+        let mut position = Position::new(
+            dai_usdc_pool(),
+            100e18 as u128,
+            nearest_usable_tick(*POOL_TICK_CURRENT, TICK_SPACING) - TICK_SPACING * 2,
+            nearest_usable_tick(*POOL_TICK_CURRENT, TICK_SPACING) + TICK_SPACING * 2,
+        );
+        assert_eq!(
+            position.amount0().unwrap().quotient().to_string(),
+            "120054069145287995769396"
+        );
+        assert_eq!(
+            position.amount1().unwrap().quotient().to_string(),
+            "79831926242"
+        );
+        let MintAmounts { amount0, amount1 } = position.mint_amounts().unwrap();
+        assert_eq!(amount0.to_string(), "120054069145287995769397");
+        assert_eq!(amount1.to_string(), "79831926243");
+    }
+
+    #[test]
+    fn from_price_range_brackets_the_prices() -> Result<()> {
+        let price_lower = Price::new(
+            DAI.clone(),
+            USDC.clone(),
+            BigInt::from(10).pow(20),
+            BigInt::from(99_000_000),
+        );
+        let price_upper = Price::new(
+            DAI.clone(),
+            USDC.clone(),
+            BigInt::from(10).pow(20),
+            BigInt::from(101_000_000),
+        );
+        let position = Position::from_price_range(
+            dai_usdc_pool(),
+            price_lower.clone(),
+            price_upper.clone(),
+            U256::from(10).pow(U256::from(18)),
+            U256::from(10).pow(U256::from(6)),
+        )?;
+        assert_eq!(position.tick_lower % TICK_SPACING, 0);
+        assert_eq!(position.tick_upper % TICK_SPACING, 0);
+        assert!(position.token0_price_lower()? <= price_lower);
+        assert!(position.token0_price_upper()? >= price_upper);
+        assert!(position.liquidity > 0);
+        Ok(())
+    }
+
+    #[test]
+    fn pool_liquidity_share() {
+        let pool = Pool::new(
+            DAI.clone(),
+            USDC.clone(),
+            FeeAmount::LOW,
+            *POOL_SQRT_RATIO_START,
+            100e18 as u128,
+            None,
+        )
+        .unwrap();
+        let tick_lower = nearest_usable_tick(*POOL_TICK_CURRENT, TICK_SPACING) - TICK_SPACING * 2;
+        let tick_upper = nearest_usable_tick(*POOL_TICK_CURRENT, TICK_SPACING) + TICK_SPACING * 2;
+
+        let position = Position::new(pool.clone(), 100e18 as u128, tick_lower, tick_upper);
+        assert_eq!(position.pool_liquidity_share(), Percent::new(100, 100));
+
+        let position = Position::new(pool.clone(), 25e18 as u128, tick_lower, tick_upper);
+        assert_eq!(position.pool_liquidity_share(), Percent::new(1, 4));
+
+        let position = Position::new(pool, 100e18 as u128, tick_upper, tick_upper + TICK_SPACING);
+        assert_eq!(position.pool_liquidity_share(), Percent::new(0, 1));
+    }
+
+    #[test]
+    fn liquidity_delta_to() {
+        let position = Position::new(dai_usdc_pool(), 100, -10, 10);
+        let larger = Position::new(dai_usdc_pool(), 250, -10, 10);
+        assert_eq!(position.liquidity_delta_to(&larger).unwrap(), 150);
+        assert_eq!(larger.liquidity_delta_to(&position).unwrap(), -150);
+        assert_eq!(position.liquidity_delta_to(&position).unwrap(), 0);
+    }
+
+    #[test]
+    fn liquidity_delta_to_beyond_i128() {
+        let empty = Position::new(dai_usdc_pool(), 0, -10, 10);
+        let max = Position::new(dai_usdc_pool(), u128::MAX, -10, 10);
+        let half = Position::new(dai_usdc_pool(), 1 << 127, -10, 10);
+        assert_eq!(max.liquidity_delta_to(&max).unwrap(), 0);
+        assert_eq!(half.liquidity_delta_to(&empty).unwrap(), i128::MIN);
+        assert!(matches!(
+            empty.liquidity_delta_to(&half).unwrap_err().downcast_ref(),
+            Some(Error::LiquidityOverflow)
+        ));
+        assert!(matches!(
+            max.liquidity_delta_to(&empty).unwrap_err().downcast_ref(),
+            Some(Error::LiquidityOverflow)
+        ));
+    }
 }
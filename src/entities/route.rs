@@ -1,23 +1,45 @@
-use crate::{entities::pool::Pool, error::Error};
+use crate::{constants::FeeAmount, entities::pool::Pool, error::Error, utils::big_int_to_i256};
+use alloy_primitives::{Address, I256};
+use num_bigint::BigInt;
+use num_traits::Zero;
+use serde::{Deserialize, Serialize};
 use uniswap_sdk_core::entities::{
-    currency::CurrencyTrait,
-    fractions::{
-        fraction::{FractionBase, FractionTrait},
-        price::Price,
-    },
+    currency::{Currency, CurrencyTrait},
+    ether::Ether,
+    fractions::{currency_amount::CurrencyAmount, fraction::FractionBase, price::Price},
     token::Token,
+    weth9::WETH9,
 };
 
+/// Represents a list of pools through which a swap can occur
+#[derive(Clone, Debug)]
 pub struct Route<TInput, TOutput>
 where
     TInput: CurrencyTrait,
     TOutput: CurrencyTrait,
 {
-    pools: Vec<Pool>,
-    token_path: Vec<Token>,
-    input: TInput,
-    output: TOutput,
-    mid_price: Option<Price<TInput, TOutput>>,
+    pub pools: Vec<Pool>,
+    pub token_path: Vec<Token>,
+    /// The input token
+    pub input: TInput,
+    /// The output token
+    pub output: TOutput,
+    _mid_price: Option<Price<TInput, TOutput>>,
+}
+
+/// A compact representation of a [`Route`] that stores only the hops and the currencies, leaving
+/// the pool state to be reloaded with [`Route::rehydrate`]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompactRoute {
+    pub chain_id: u32,
+    /// The input token address and the fee of each pool, in order
+    pub hops: Vec<(Address, FeeAmount)>,
+    /// The address of the wrapped output token
+    pub output: Address,
+    /// Whether the input is the native currency rather than its wrapped token
+    pub input_is_native: bool,
+    /// Whether the output is the native currency rather than its wrapped token
+    pub output_is_native: bool,
 }
 
 impl<TInput, TOutput> Route<TInput, TOutput>
@@ -33,7 +55,7 @@ where
     /// * `inpit`: The other token in the pool
     /// * `output`: The fee in hundredths of a bips of the input amount of every swap that is collected by the pool
     pub fn new(pools: Vec<Pool>, input: TInput, output: TOutput) -> Result<Self, Error> {
-        if pools.len() == 0 {
+        if pools.is_empty() {
             return Err(Error::IsZero);
         }
 
@@ -43,6 +65,70 @@ where
             return Err(Error::ChainIdIsDifferent);
         }
 
+        Self::build(pools, input, output, true)
+    }
+
+    /// Construct a single hop route through the pool with the most in range liquidity among the
+    /// pools of the input and output pair, e.g. the same pair at different fee tiers
+    ///
+    /// # Arguments
+    ///
+    /// * `pools_for_pair`: The candidate pools, of which those not of the pair are ignored
+    /// * `input`: The input currency
+    /// * `output`: The output currency
+    ///
+    /// returns: The route, or [`Error::NoPoolForPair`] if no pool connects the pair
+    ///
+    pub fn direct(pools_for_pair: &[Pool], input: TInput, output: TOutput) -> Result<Self, Error> {
+        let (token_in, token_out) = (input.wrapped(), output.wrapped());
+        let pool = pools_for_pair
+            .iter()
+            .filter(|pool| pool.involves_token(&token_in) && pool.involves_token(&token_out))
+            .max_by_key(|pool| pool.liquidity)
+            .ok_or(Error::NoPoolForPair)?;
+        Self::new(vec![pool.clone()], input, output)
+    }
+
+    /// Construct a Route whose pools may be on different chains, for tooling that models
+    /// hypothetical cross-chain routes. This is an expert path: only the chain id uniformity
+    /// check of [`Route::new`] is skipped and [`Route::chain_id`] returns the chain of the first
+    /// pool. The token path is still validated, with consecutive hops connected by token address
+    /// so that a token bridged to the same address links pools on different chains.
+    ///
+    /// The pools, token path and [`Route::mid_price`] of such a route are meaningful, but a swap
+    /// cannot be simulated across chains, so [`Route::simulate_exact_in`] and the trade
+    /// constructors that simulate swaps return [`Error::ChainIdIsDifferent`] for it.
+    ///
+    /// # Arguments
+    ///
+    /// * `pools`: array of pools
+    /// * `input`: The input currency
+    /// * `output`: The output currency
+    pub fn new_unchecked_chain(
+        pools: Vec<Pool>,
+        input: TInput,
+        output: TOutput,
+    ) -> Result<Self, Error> {
+        Self::build(pools, input, output, false)
+    }
+
+    fn build(
+        pools: Vec<Pool>,
+        input: TInput,
+        output: TOutput,
+        same_chain: bool,
+    ) -> Result<Self, Error> {
+        if pools.is_empty() {
+            return Err(Error::IsZero);
+        }
+        let connects = |a: &Token, b: &Token| {
+            if same_chain {
+                a.equals(b)
+            } else {
+                a.address() == b.address()
+            }
+        };
+
         let wrapped_input = input.wrapped().clone();
         if !(pools[pools.len() - 1].involves_token(&output.wrapped())) {
             return Err(Error::InvolvesToken);
@@ -52,25 +138,34 @@ where
 
         for (i, pool) in pools.iter().enumerate() {
             let current_input_token = &token_path[i];
-            if !(current_input_token.equals(&pool.token0)
-                || current_input_token.equals(&pool.token1))
+            if !(connects(current_input_token, &pool.token0)
+                || connects(current_input_token, &pool.token1))
             {
-                return Err(Error::TokenNotInPool);
+                // the first pool must contain the input, later pools must continue the path
+                return Err(if i == 0 {
+                    Error::TokenNotInPool(i)
+                } else {
+                    Error::DisconnectedRoute(i)
+                });
             }
-            let next_token = if current_input_token.equals(&pool.token0) {
+            let next_token = if connects(current_input_token, &pool.token0) {
                 &pool.token1
             } else {
                 &pool.token0
             };
             token_path.push(next_token.clone());
         }
+        // the last pool involving the output doesn't imply that the path ends at it
+        if !token_path.last().unwrap().equals(&output.wrapped()) {
+            return Err(Error::InvolvesToken);
+        }
 
         Ok(Self {
-            pools: pools,
-            token_path: token_path,
-            input: input,
-            output: output,
-            mid_price: None,
+            pools,
+            token_path,
+            input,
+            output,
+            _mid_price: None,
         })
     }
 
@@ -78,57 +173,406 @@ where
         self.pools[0].chain_id()
     }
 
-    pub fn mid_price(&mut self) -> Price<TInput, TOutput> {
-        if self.mid_price.is_none() {
-            let token0_price = self.pools[0].token0_price().clone();
-            let token1_price = self.pools[0].token1_price().clone();
-            let initial_price = if self.pools[0].token0 == self.input.wrapped() {
-                token1_price
-            } else {
-                token0_price
-            };
-            let price = self
-                .pools
-                .iter()
-                .skip(1)
-                .fold(
-                    (
-                        if self.pools[0].token0 == self.input.wrapped() {
-                            &self.pools[0].token1
-                        } else {
-                            &self.pools[0].token0
-                        },
-                        initial_price,
-                    ),
-                    |(next_input, price), pool| {
-                        if next_input == &pool.token0 {
-                            (
-                                &pool.token1,
-                                price
-                                    .multiply(&pool.clone().token0_price())
-                                    .expect("Failed to multiply prices"),
-                            )
-                        } else {
-                            (
-                                &pool.token0,
-                                price
-                                    .multiply(&pool.clone().token1_price())
-                                    .expect("Failed to multiply Prices"),
-                            )
-                        }
-                    },
-                )
-                .1;
-
-            Price::new(
+    /// Whether the pools of the route are on more than one chain, which is only possible for
+    /// routes made with [`Route::new_unchecked_chain`]
+    pub(crate) fn spans_chains(&self) -> bool {
+        let chain_id = self.chain_id();
+        self.pools.iter().any(|pool| pool.chain_id() != chain_id)
+    }
+
+    /// Appends a pool to the route, producing a route with one more hop to a new output
+    ///
+    /// # Arguments
+    ///
+    /// * `next_pool`: The pool to append, which must involve the current last token of the path
+    /// * `new_output`: The output of the extended route
+    pub fn extend<TNewOutput: CurrencyTrait>(
+        self,
+        next_pool: Pool,
+        new_output: TNewOutput,
+    ) -> Result<Route<TInput, TNewOutput>, Error> {
+        if next_pool.chain_id() != self.chain_id() {
+            return Err(Error::ChainIdIsDifferent);
+        }
+        let current_output_token = self.token_path.last().unwrap();
+        let next_token = if current_output_token.equals(&next_pool.token0) {
+            next_pool.token1.clone()
+        } else if current_output_token.equals(&next_pool.token1) {
+            next_pool.token0.clone()
+        } else {
+            return Err(Error::DisconnectedRoute(self.pools.len()));
+        };
+        // the new output must be the token the appended hop swaps to
+        if !next_token.equals(&new_output.wrapped()) {
+            return Err(Error::InvolvesToken);
+        }
+
+        let Self {
+            mut pools,
+            mut token_path,
+            input,
+            ..
+        } = self;
+        pools.push(next_pool);
+        token_path.push(next_token);
+        Ok(Route {
+            pools,
+            token_path,
+            input,
+            output: new_output,
+            _mid_price: None,
+        })
+    }
+
+    /// Replaces the input and output currencies of the route with currencies of other types that
+    /// wrap to the same tokens, e.g. native ether and WETH, keeping the pools and the token path
+    ///
+    /// # Arguments
+    ///
+    /// * `input`: The new input, which must wrap to the first token of the path
+    /// * `output`: The new output, which must wrap to the last token of the path
+    pub fn map_currencies<TNewInput: CurrencyTrait, TNewOutput: CurrencyTrait>(
+        self,
+        input: TNewInput,
+        output: TNewOutput,
+    ) -> Result<Route<TNewInput, TNewOutput>, Error> {
+        if !input.wrapped().equals(&self.token_path[0]) {
+            return Err(Error::TokenNotInPool(0));
+        }
+        if !output.wrapped().equals(self.token_path.last().unwrap()) {
+            return Err(Error::InvolvesToken);
+        }
+        Ok(Route {
+            pools: self.pools,
+            token_path: self.token_path,
+            input,
+            output,
+            _mid_price: None,
+        })
+    }
+
+    /// Returns the number of pools in the route
+    pub fn len(&self) -> usize {
+        self.pools.len()
+    }
+
+    /// Returns true if the route has no pools, which is never the case for a route built with
+    /// [`Route::new`]
+    pub fn is_empty(&self) -> bool {
+        self.pools.is_empty()
+    }
+
+    /// Returns the pool at the given hop, or `None` if `i` is out of bounds
+    pub fn pool_at(&self, i: usize) -> Option<&Pool> {
+        self.pools.get(i)
+    }
+
+    /// Returns the tokens the route hops through, i.e. the token path without the input and output
+    pub fn intermediate_tokens(&self) -> &[Token] {
+        &self.token_path[1..self.token_path.len() - 1]
+    }
+
+    /// Returns true if the wrapped native token of the route's chain, e.g. WETH, is anywhere in the
+    /// token path, including as an intermediate hop
+    pub fn involves_weth(&self) -> bool {
+        WETH9::new()
+            .get(self.chain_id())
+            .is_some_and(|weth| self.token_path.iter().any(|token| token.equals(weth)))
+    }
+
+    /// Returns true if the output is the native currency, so that the wrapped native token at the
+    /// end of the token path must be unwrapped for the recipient
+    pub fn unwraps_to_native(&self) -> bool {
+        self.output.is_native()
+    }
+
+    /// Returns true if every pool in the route has tick data, so that the route can be simulated
+    /// without failing at a pool that was created without a tick data provider
+    pub fn is_simulatable(&self) -> bool {
+        self.pools
+            .iter()
+            .all(|pool| pool.tick_data_provider.has_ticks())
+    }
+
+    /// Returns the mid price of the route
+    ///
+    /// Errors with [`Error::TokenNotInPool`] if the wrapped input is not a side of the first pool,
+    /// which can only happen if the public fields were modified after construction, with
+    /// [`Error::DisconnectedRoute`] if a later pool no longer continues the path for the same
+    /// reason, and with [`Error::DegeneratePrice`] if the route goes through a pool with a zero
+    /// sqrt price, which would make the price zero or infinite.
+    pub fn mid_price(&mut self) -> Result<Price<TInput, TOutput>, Error> {
+        if self._mid_price.is_none() {
+            let input = self.input.wrapped();
+            if !self.pools[0].token0.equals(&input) && !self.pools[0].token1.equals(&input) {
+                return Err(Error::TokenNotInPool(0));
+            }
+            // a zero sqrt price makes the pool price zero one way and divide by zero the other way
+            if self.pools.iter().any(|pool| pool.sqrt_ratio_x96.is_zero()) {
+                return Err(Error::DegeneratePrice);
+            }
+            // hops are connected by address like in the constructors, so that the price of a
+            // route made with `Route::new_unchecked_chain` composes across bridged tokens
+            let mut next_input = input.clone();
+            let mut numerator = BigInt::from(1);
+            let mut denominator = BigInt::from(1);
+            for (i, pool) in self.pools.iter_mut().enumerate() {
+                let pool_price = if next_input.address() == pool.token0.address() {
+                    next_input = pool.token1.clone();
+                    pool.token0_price()
+                } else if next_input.address() == pool.token1.address() {
+                    next_input = pool.token0.clone();
+                    pool.token1_price()
+                } else {
+                    return Err(Error::DisconnectedRoute(i));
+                };
+                numerator *= pool_price.numerator();
+                denominator *= pool_price.denominator();
+            }
+            let price = Price::new(input, next_input, denominator, numerator);
+
+            let mut mid_price = Price::new(
                 self.input.clone(),
                 self.output.clone(),
                 price.denominator().clone(),
                 price.numerator().clone(),
-            )
-        } else {
-            self.mid_price.clone().unwrap()
+            );
+            // the ratio is between the wrapped tokens, so scale it by their decimals as well
+            mid_price.meta.scalar = price.meta.scalar;
+            self._mid_price = Some(mid_price);
+        }
+        Ok(self._mid_price.clone().unwrap())
+    }
+
+    /// Returns the mid price of the route expressed as input per output, i.e. the reciprocal of
+    /// [`Route::mid_price`]
+    pub fn mid_price_inverted(&mut self) -> Result<Price<TOutput, TInput>, Error> {
+        Ok(self.mid_price()?.invert())
+    }
+
+    /// Returns the mid price of the route expressed in a quote token, by composing it with the mid
+    /// price of a pool of the route output and the quote token
+    ///
+    /// # Arguments
+    ///
+    /// * `pricing_pool`: A pool of the wrapped route output and the quote token
+    /// * `quote`: The token to express the price in
+    ///
+    pub fn mid_price_in(
+        &mut self,
+        pricing_pool: &Pool,
+        quote: &Token,
+    ) -> Result<Price<TInput, Token>, Error> {
+        let output = self.output.wrapped();
+        if output.equals(quote)
+            || !pricing_pool.involves_token(&output)
+            || !pricing_pool.involves_token(quote)
+        {
+            return Err(Error::InvolvesToken);
+        }
+        let mid_price = self.mid_price()?;
+        let output_price = pricing_pool.clone().price_of(&output);
+        Ok(Price::new(
+            self.input.clone(),
+            quote.clone(),
+            mid_price.denominator() * output_price.denominator(),
+            mid_price.numerator() * output_price.numerator(),
+        ))
+    }
+
+    /// Simulates an exact input swap through the route, returning the output amount and the pools
+    /// with their state after the swap, so that a following simulation can see the moved prices
+    ///
+    /// # Arguments
+    ///
+    /// * `amount_in`: The amount of the input currency to swap
+    ///
+    /// returns: The output amount and the post-swap pools, in the order of the route
+    ///
+    pub fn simulate_exact_in(
+        &self,
+        amount_in: CurrencyAmount<TInput>,
+    ) -> anyhow::Result<(CurrencyAmount<TOutput>, Vec<Pool>)> {
+        assert!(amount_in.meta.currency.equals(&self.input), "INPUT");
+        if self.spans_chains() {
+            return Err(Error::ChainIdIsDifferent.into());
+        }
+        let mut token_amount = amount_in.wrapped()?;
+        let mut pools = Vec::with_capacity(self.pools.len());
+        for pool in &self.pools {
+            let pool_after;
+            (token_amount, pool_after) = pool.get_output_amount(token_amount, None)?;
+            pools.push(pool_after);
+        }
+        Ok((
+            CurrencyAmount::from_fractional_amount(
+                self.output.clone(),
+                token_amount.numerator(),
+                token_amount.denominator(),
+            )?,
+            pools,
+        ))
+    }
+
+    /// Returns the effective price of the route for a specific input amount, i.e. the output of
+    /// [`Route::simulate_exact_in`] divided by the input
+    ///
+    /// # Arguments
+    ///
+    /// * `amount_in`: The amount of the input currency to price
+    ///
+    pub fn price_for_amount(
+        &self,
+        amount_in: CurrencyAmount<TInput>,
+    ) -> anyhow::Result<Price<TInput, TOutput>> {
+        if amount_in.quotient().is_zero() {
+            return Err(Error::ZeroAmount.into());
+        }
+        let (amount_out, _) = self.simulate_exact_in(amount_in.clone())?;
+        Ok(Price::new(
+            self.input.clone(),
+            self.output.clone(),
+            amount_in.quotient(),
+            amount_out.quotient(),
+        ))
+    }
+
+    /// Returns the mid price of the route after swapping the input amount, i.e. the marginal price
+    /// of the next unit, as opposed to the average price of [`Route::price_for_amount`]
+    ///
+    /// # Arguments
+    ///
+    /// * `amount_in`: The amount of the input currency to swap before pricing
+    ///
+    pub fn end_price_after(
+        &self,
+        amount_in: CurrencyAmount<TInput>,
+    ) -> anyhow::Result<Price<TInput, TOutput>> {
+        let (_, pools) = self.simulate_exact_in(amount_in)?;
+        let mut route = self.clone();
+        route.pools = pools;
+        route.invalidate_mid_price();
+        Ok(route.mid_price()?)
+    }
+
+    /// Returns true if the route starts and ends at the same token, treating native currencies as
+    /// their wrapped tokens, i.e. the route is an arbitrage cycle
+    pub fn is_cycle(&self) -> bool {
+        self.input.wrapped().equals(&self.output.wrapped())
+    }
+
+    /// Returns the output minus the input of swapping the input amount through a cyclic route, which
+    /// is positive if the cycle is profitable
+    ///
+    /// # Arguments
+    ///
+    /// * `amount_in`: The amount of the input currency to swap around the cycle
+    ///
+    pub fn cycle_profit(&self, amount_in: CurrencyAmount<TInput>) -> anyhow::Result<I256> {
+        assert!(self.is_cycle(), "CYCLE");
+        let (amount_out, _) = self.simulate_exact_in(amount_in.clone())?;
+        Ok(big_int_to_i256(
+            amount_out.quotient() - amount_in.quotient(),
+        ))
+    }
+
+    /// Returns the compact representation of the route, see [`CompactRoute`]
+    pub fn compact(&self) -> CompactRoute {
+        CompactRoute {
+            chain_id: self.chain_id(),
+            hops: self
+                .pools
+                .iter()
+                .zip(&self.token_path)
+                .map(|(pool, token)| (token.address(), pool.fee))
+                .collect(),
+            output: self.token_path.last().unwrap().address(),
+            input_is_native: self.input.is_native(),
+            output_is_native: self.output.is_native(),
+        }
+    }
+
+    /// Clears the cached mid price so that it is recomputed from the current pool state on the next
+    /// call to [`Route::mid_price`]. Call this after mutating the state of any pool in the route.
+    pub fn invalidate_mid_price(&mut self) {
+        self._mid_price = None;
+    }
+}
+
+impl Route<Currency, Currency> {
+    /// Rebuild a route from its compact representation, loading each pool with `pool_lookup`
+    ///
+    /// # Arguments
+    ///
+    /// * `compact`: The compact route
+    /// * `pool_lookup`: Returns the pool for the given pair of token addresses and fee
+    ///
+    /// returns: Result<Route<Currency, Currency>, Error>
+    ///
+    pub fn rehydrate(
+        compact: &CompactRoute,
+        mut pool_lookup: impl FnMut(Address, Address, FeeAmount) -> Result<Pool, Error>,
+    ) -> Result<Self, Error> {
+        if compact.hops.is_empty() {
+            return Err(Error::IsZero);
         }
+        let token_outs = compact
+            .hops
+            .iter()
+            .skip(1)
+            .map(|(token, _)| *token)
+            .chain([compact.output]);
+        let pools = compact
+            .hops
+            .iter()
+            .zip(token_outs)
+            .map(|(&(token_in, fee), token_out)| pool_lookup(token_in, token_out, fee))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let token = |pool: &Pool, address: Address| {
+            if pool.token0.address() == address {
+                pool.token0.clone()
+            } else {
+                pool.token1.clone()
+            }
+        };
+        let input = if compact.input_is_native {
+            Currency::NativeCurrency(Ether::on_chain(compact.chain_id))
+        } else {
+            Currency::Token(token(&pools[0], compact.hops[0].0))
+        };
+        let output = if compact.output_is_native {
+            Currency::NativeCurrency(Ether::on_chain(compact.chain_id))
+        } else {
+            Currency::Token(token(pools.last().unwrap(), compact.output))
+        };
+        Self::new(pools, input, output)
+    }
+}
+
+impl<TInput, TOutput> PartialEq for Route<TInput, TOutput>
+where
+    TInput: CurrencyTrait + PartialEq,
+    TOutput: CurrencyTrait + PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.pools == other.pools
+            && self.token_path == other.token_path
+            && self.input == other.input
+            && self.output == other.output
+    }
+}
+
+impl<TInput, TOutput> TryFrom<(Vec<Pool>, TInput, TOutput)> for Route<TInput, TOutput>
+where
+    TInput: CurrencyTrait,
+    TOutput: CurrencyTrait,
+{
+    type Error = Error;
+
+    /// Construct a Route from a tuple of pools, input and output, see [`Route::new`]
+    fn try_from((pools, input, output): (Vec<Pool>, TInput, TOutput)) -> Result<Self, Error> {
+        Self::new(pools, input, output)
     }
 }
 
@@ -138,17 +582,25 @@ mod tests {
 
     use crate::{
         constants::FeeAmount,
-        entities::{pool::Pool, route::Route, Tick, TickListDataProvider},
+        entities::{
+            pool::{full_range_pool, Pool},
+            route::{CompactRoute, CurrencyAmount, Route},
+            Tick, TickListDataProvider,
+        },
+        error::Error,
         prelude::{encode_sqrt_ratio_x96, nearest_usable_tick},
     };
+    use alloy_primitives::{address, I256, U256};
     use uniswap_sdk_core::{
+        constants::Rounding,
         entities::{
+            currency::{Currency, CurrencyTrait},
             ether::Ether,
+            fractions::fraction::FractionBase,
             token::Token,
-            weth9::{self, WETH9}, fractions::fraction::FractionBase,
+            weth9::WETH9,
         },
-        prelude::RoundingMode,
-        token, constants::Rounding,
+        token,
     };
     use uniswap_v3_math::tick_math;
 
@@ -297,20 +749,16 @@ mod tests {
     }
 
     #[test]
-    fn test_mid_price() {
-        let eth = Ether::on_chain(1);
+    fn test_len_and_pool_at() {
         let token0 = token!(1, "0x0000000000000000000000000000000000000001", 18, "t0");
         let token1 = token!(1, "0x0000000000000000000000000000000000000002", 18, "t1");
         let token2 = token!(1, "0x0000000000000000000000000000000000000003", 18, "t2");
 
-        let weth_instance = WETH9::new();
-        let weth = weth_instance.get(1).unwrap();
-
         let pool_0_1 = Pool::new(
             token0.clone(),
             token1.clone(),
             FeeAmount::MEDIUM,
-            encode_sqrt_ratio_x96(1, 5),
+            encode_sqrt_ratio_x96(1, 1),
             0,
             None,
         )
@@ -319,56 +767,899 @@ mod tests {
             token1.clone(),
             token2.clone(),
             FeeAmount::MEDIUM,
-            encode_sqrt_ratio_x96(15, 30),
+            encode_sqrt_ratio_x96(1, 1),
             0,
-            Some(Arc::new(TickListDataProvider::new(
-                vec![Tick::new(
-                    nearest_usable_tick(tick_math::MIN_TICK, FeeAmount::LOW.tick_spacing()),
-                    1,
-                    5,
-                )],
-                FeeAmount::LOW.tick_spacing(),
-            ))),
+            None,
         )
         .unwrap();
-        let pool_0_weth = Pool::new(
+        let route = Route::new(
+            vec![pool_0_1.clone(), pool_1_2.clone()],
             token0.clone(),
-            weth.clone(),
+            token2.clone(),
+        )
+        .unwrap();
+
+        assert_eq!(route.len(), route.pools.len());
+        assert!(!route.is_empty());
+        assert_eq!(route.pool_at(0), Some(&pool_0_1));
+        assert_eq!(route.pool_at(1), Some(&pool_1_2));
+        assert_eq!(route.pool_at(2), None);
+    }
+
+    #[test]
+    fn test_new_unchecked_chain() {
+        let token0 = token!(1, "0x0000000000000000000000000000000000000001", 18, "t0");
+        let token1 = token!(1, "0x0000000000000000000000000000000000000002", 18, "t1");
+        let bridged1 = token!(10, "0x0000000000000000000000000000000000000002", 18, "t1");
+        let token2 = token!(10, "0x0000000000000000000000000000000000000003", 18, "t2");
+
+        let pool = |a: &Token, b: &Token| {
+            Pool::new(
+                a.clone(),
+                b.clone(),
+                FeeAmount::MEDIUM,
+                encode_sqrt_ratio_x96(1, 1),
+                0,
+                None,
+            )
+            .unwrap()
+        };
+        let pools = vec![pool(&token0, &token1), pool(&bridged1, &token2)];
+        assert!(matches!(
+            Route::new(pools.clone(), token0.clone(), token2.clone()),
+            Err(Error::ChainIdIsDifferent)
+        ));
+
+        let mut route = Route::new_unchecked_chain(pools, token0.clone(), token2.clone()).unwrap();
+        assert_eq!(
+            route.token_path,
+            vec![token0.clone(), token1, token2.clone()]
+        );
+        assert_eq!(route.chain_id(), 1);
+
+        // the mid price composes across the bridged token
+        let mid_price = route.mid_price().unwrap();
+        assert_eq!(mid_price.meta.base_currency, token0);
+        assert_eq!(mid_price.meta.quote_currency, token2);
+        assert_eq!(mid_price.to_fixed(4, Rounding::RoundHalfUp), "1.0000");
+
+        // but swaps cannot be simulated across chains
+        let amount_in = CurrencyAmount::from_raw_amount(token0.clone(), 100).unwrap();
+        let err = route.simulate_exact_in(amount_in.clone()).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref(),
+            Some(Error::ChainIdIsDifferent)
+        ));
+        let err = crate::entities::Trade::exact_in(route, amount_in).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref(),
+            Some(Error::ChainIdIsDifferent)
+        ));
+
+        // the token path is still validated
+        assert!(matches!(
+            Route::new_unchecked_chain(vec![pool(&bridged1, &token2)], token0, token2),
+            Err(Error::TokenNotInPool(0))
+        ));
+    }
+
+    #[test]
+    fn test_path_must_end_at_output() {
+        let token0 = token!(1, "0x0000000000000000000000000000000000000001", 18, "t0");
+        let token1 = token!(1, "0x0000000000000000000000000000000000000002", 18, "t1");
+        let pool = full_range_pool(&token0, &token1, FeeAmount::MEDIUM, 1_000_000);
+
+        // the only pool involves the output, but the path ends at token1
+        assert!(matches!(
+            Route::new(vec![pool.clone()], token0.clone(), token0.clone()),
+            Err(Error::InvolvesToken)
+        ));
+        assert!(matches!(
+            Route::new_unchecked_chain(vec![pool.clone()], token0.clone(), token0.clone()),
+            Err(Error::InvolvesToken)
+        ));
+        let route = Route::new(vec![pool], token0, token1.clone()).unwrap();
+        assert_eq!(route.token_path.last(), Some(&token1));
+    }
+
+    #[test]
+    fn test_broken_path_reports_failing_pool_index() {
+        let token0 = token!(1, "0x0000000000000000000000000000000000000001", 18, "t0");
+        let token1 = token!(1, "0x0000000000000000000000000000000000000002", 18, "t1");
+        let token2 = token!(1, "0x0000000000000000000000000000000000000003", 18, "t2");
+        let token3 = token!(1, "0x0000000000000000000000000000000000000004", 18, "t3");
+
+        let pool = |a: &Token, b: &Token| {
+            Pool::new(
+                a.clone(),
+                b.clone(),
+                FeeAmount::MEDIUM,
+                encode_sqrt_ratio_x96(1, 1),
+                0,
+                None,
+            )
+            .unwrap()
+        };
+        let pools = vec![
+            pool(&token0, &token1),
+            pool(&token1, &token2),
+            pool(&token0, &token3),
+        ];
+        assert!(matches!(
+            Route::try_from((pools, token0.clone(), token3.clone())),
+            Err(Error::DisconnectedRoute(2))
+        ));
+
+        let pools = vec![
+            pool(&token0, &token1),
+            pool(&token2, &token3),
+            pool(&token1, &token3),
+        ];
+        assert!(matches!(
+            Route::new(pools, token0.clone(), token3.clone()),
+            Err(Error::DisconnectedRoute(1))
+        ));
+
+        // a first pool without the input is not a disconnect
+        assert!(matches!(
+            Route::new(vec![pool(&token1, &token3)], token0.clone(), token3.clone()),
+            Err(Error::TokenNotInPool(0))
+        ));
+
+        let pools = vec![
+            pool(&token0, &token1),
+            pool(&token1, &token2),
+            pool(&token2, &token3),
+        ];
+        let route = Route::try_from((pools, token0, token3.clone())).unwrap();
+        assert_eq!(route.token_path.last(), Some(&token3));
+    }
+
+    #[test]
+    fn test_native_input_wraps_to_chain_wrapped_native() {
+        let matic = Ether::on_chain(137);
+        let token0 = token!(137, "0x0000000000000000000000000000000000000001", 18, "t0");
+        let wmatic = WETH9::new().get(137).unwrap().clone();
+
+        let pool_0_wmatic = Pool::new(
+            token0.clone(),
+            wmatic.clone(),
             FeeAmount::MEDIUM,
             encode_sqrt_ratio_x96(1, 1),
             0,
-            Some(Arc::new(TickListDataProvider::new(
-                vec![Tick::new(
-                    nearest_usable_tick(tick_math::MIN_TICK, FeeAmount::LOW.tick_spacing()),
-                    15,
-                    30,
-                )],
-                FeeAmount::LOW.tick_spacing(),
-            ))),
+            None,
         )
         .unwrap();
-        let pool_1_weth = Pool::new(
+        let route = Route::new(vec![pool_0_wmatic], matic, token0.clone()).unwrap();
+
+        assert_eq!(
+            route.token_path[0].address(),
+            address!("0d500B1d8E8eF31E21C99d1Db9A6444d3ADf1270")
+        );
+        assert_eq!(route.token_path[0].symbol.as_deref(), Some("WMATIC"));
+        assert_eq!(route.token_path[1], token0);
+    }
+
+    #[test]
+    fn test_extend() {
+        let token0 = token!(1, "0x0000000000000000000000000000000000000001", 18, "t0");
+        let token1 = token!(1, "0x0000000000000000000000000000000000000002", 18, "t1");
+        let token2 = token!(1, "0x0000000000000000000000000000000000000003", 18, "t2");
+        let pool_0_1 = Pool::new(
+            token0.clone(),
             token1.clone(),
-            weth.clone(),
             FeeAmount::MEDIUM,
             encode_sqrt_ratio_x96(1, 1),
             0,
-            Some(Arc::new(TickListDataProvider::new(
-                vec![Tick::new(
-                    nearest_usable_tick(tick_math::MIN_TICK, FeeAmount::LOW.tick_spacing()),
-                    1,
-                    7,
-                )],
-                FeeAmount::LOW.tick_spacing(),
-            ))),
+            None,
+        )
+        .unwrap();
+        let pool_1_2 = Pool::new(
+            token1.clone(),
+            token2.clone(),
+            FeeAmount::MEDIUM,
+            encode_sqrt_ratio_x96(1, 1),
+            0,
+            None,
         )
         .unwrap();
+        let route = Route::new(vec![pool_0_1.clone()], token0.clone(), token1.clone()).unwrap();
 
-        //IT CORRECT FOR 0 -> 1
-        let price = Route::new(vec![pool_0_1.clone()], token0.clone(), token1.clone())
-            .unwrap()
-            .mid_price
+        let extended = route
+            .clone()
+            .extend(pool_1_2.clone(), token2.clone())
             .unwrap();
-        assert_eq!(price.to_fixed(0, Rounding::RoundDown), "0.2000".to_string());
+        assert_eq!(extended.pools, vec![pool_0_1, pool_1_2.clone()]);
+        assert_eq!(
+            extended.token_path,
+            vec![token0.clone(), token1.clone(), token2.clone()]
+        );
+        assert_eq!(extended.input, token0);
+        assert_eq!(extended.output, token2);
+
+        // the pool involves the new output, but the extended path ends at token2
+        assert!(matches!(
+            route.clone().extend(pool_1_2, token1.clone()),
+            Err(Error::InvolvesToken)
+        ));
+
+        // the pool does not connect to the current output token
+        let pool_0_2 = Pool::new(
+            token0.clone(),
+            token2.clone(),
+            FeeAmount::MEDIUM,
+            encode_sqrt_ratio_x96(1, 1),
+            0,
+            None,
+        )
+        .unwrap();
+        assert!(matches!(
+            route.extend(pool_0_2, token2),
+            Err(Error::DisconnectedRoute(1))
+        ));
+    }
+
+    #[test]
+    fn test_compact_round_trip() {
+        let token0 = token!(1, "0x0000000000000000000000000000000000000001", 18, "t0");
+        let token1 = token!(1, "0x0000000000000000000000000000000000000002", 18, "t1");
+        let weth = WETH9::new().get(1).unwrap().clone();
+
+        let pools = [
+            Pool::new(
+                token0.clone(),
+                token1.clone(),
+                FeeAmount::MEDIUM,
+                encode_sqrt_ratio_x96(1, 1),
+                0,
+                None,
+            )
+            .unwrap(),
+            Pool::new(
+                token1.clone(),
+                weth.clone(),
+                FeeAmount::LOW,
+                encode_sqrt_ratio_x96(1, 1),
+                0,
+                None,
+            )
+            .unwrap(),
+        ];
+        let route = Route::new(
+            pools.to_vec(),
+            Currency::Token(token0),
+            Currency::NativeCurrency(Ether::on_chain(1)),
+        )
+        .unwrap();
+
+        let json = serde_json::to_string(&route.compact()).unwrap();
+        let compact: CompactRoute = serde_json::from_str(&json).unwrap();
+        assert_eq!(compact.hops.len(), 2);
+        assert!(compact.output_is_native);
+
+        let rehydrated = Route::rehydrate(&compact, |token_a, token_b, fee| {
+            pools
+                .iter()
+                .find(|pool| {
+                    pool.fee == fee
+                        && [token_a, token_b].contains(&pool.token0.address())
+                        && [token_a, token_b].contains(&pool.token1.address())
+                })
+                .cloned()
+                .ok_or(Error::IsZero)
+        })
+        .unwrap();
+        assert_eq!(rehydrated, route);
+    }
+
+    #[test]
+    fn test_simulate_exact_in_in_sequence() {
+        let token0 = token!(1, "0x0000000000000000000000000000000000000001", 18, "t0");
+        let token1 = token!(1, "0x0000000000000000000000000000000000000002", 18, "t1");
+        let pool = full_range_pool(
+            &token0,
+            &token1,
+            FeeAmount::MEDIUM,
+            1_000_000_000_000_000_000,
+        );
+        let route = Route::new(vec![pool.clone()], token0.clone(), token1.clone()).unwrap();
+        let amount_in = CurrencyAmount::from_raw_amount(token0.clone(), 10u128.pow(16)).unwrap();
+
+        let (first_output, pools) = route.simulate_exact_in(amount_in.clone()).unwrap();
+        assert!(pools[0].sqrt_ratio_x96 < pool.sqrt_ratio_x96);
+
+        let route = Route::new(pools, token0, token1).unwrap();
+        let (second_output, _) = route.simulate_exact_in(amount_in).unwrap();
+        assert!(second_output.quotient() < first_output.quotient());
+    }
+
+    #[test]
+    fn test_price_for_amount_degrades_with_size() {
+        let token0 = token!(1, "0x0000000000000000000000000000000000000001", 18, "t0");
+        let token1 = token!(1, "0x0000000000000000000000000000000000000002", 18, "t1");
+        let pool = full_range_pool(&token0, &token1, FeeAmount::MEDIUM, 1_000_000);
+        let route = Route::new(vec![pool], token0.clone(), token1.clone()).unwrap();
+
+        let prices = [1_000, 10_000, 100_000, 1_000_000]
+            .into_iter()
+            .map(|amount| {
+                route
+                    .price_for_amount(
+                        CurrencyAmount::from_raw_amount(token0.clone(), amount).unwrap(),
+                    )
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+        for price in &prices {
+            assert!(price.meta.base_currency.equals(&token0));
+            assert!(price.meta.quote_currency.equals(&token1));
+        }
+        for pair in prices.windows(2) {
+            assert!(pair[1] < pair[0]);
+        }
+    }
+
+    #[test]
+    fn test_end_price_after_is_worse_than_mid_price() {
+        let token0 = token!(1, "0x0000000000000000000000000000000000000001", 18, "t0");
+        let token1 = token!(1, "0x0000000000000000000000000000000000000002", 18, "t1");
+        let pool = full_range_pool(&token0, &token1, FeeAmount::MEDIUM, 1_000_000);
+        let mut route = Route::new(vec![pool], token0.clone(), token1).unwrap();
+
+        let end_price = route
+            .end_price_after(CurrencyAmount::from_raw_amount(token0, 10_000).unwrap())
+            .unwrap();
+        let mid_price = route.mid_price().unwrap();
+        assert!(end_price < mid_price);
+        // the marginal price is worse than the average price of the swap
+        assert!(
+            end_price
+                < route
+                    .price_for_amount(
+                        CurrencyAmount::from_raw_amount(route.input.clone(), 10_000).unwrap()
+                    )
+                    .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_involves_weth() {
+        let token0 = token!(1, "0x0000000000000000000000000000000000000001", 18, "t0");
+        let token1 = token!(1, "0x0000000000000000000000000000000000000002", 18, "t1");
+        let weth = WETH9::new().get(1).unwrap().clone();
+
+        let pool = |a: &Token, b: &Token| {
+            Pool::new(
+                a.clone(),
+                b.clone(),
+                FeeAmount::MEDIUM,
+                encode_sqrt_ratio_x96(1, 1),
+                0,
+                None,
+            )
+            .unwrap()
+        };
+        let route = Route::new(
+            vec![pool(&token0, &weth), pool(&weth, &token1)],
+            token0.clone(),
+            token1.clone(),
+        )
+        .unwrap();
+        assert!(route.involves_weth());
+
+        let route = Route::new(vec![pool(&token0, &token1)], token0, token1).unwrap();
+        assert!(!route.involves_weth());
+    }
+
+    #[test]
+    fn test_direct() {
+        let token0 = token!(1, "0x0000000000000000000000000000000000000001", 18, "t0");
+        let token1 = token!(1, "0x0000000000000000000000000000000000000002", 18, "t1");
+        let token2 = token!(1, "0x0000000000000000000000000000000000000003", 18, "t2");
+        let shallow = full_range_pool(&token0, &token1, FeeAmount::MEDIUM, 1_000);
+        let deep = full_range_pool(&token0, &token1, FeeAmount::HIGH, 1_000_000);
+        let other_pair = full_range_pool(&token1, &token2, FeeAmount::MEDIUM, 1_000_000_000);
+        let pools = vec![shallow.clone(), deep.clone(), other_pair];
+
+        let route = Route::direct(&pools, token0.clone(), token1.clone()).unwrap();
+        assert_eq!(route.pools, vec![deep.clone()]);
+        assert_eq!(route.token_path, vec![token0.clone(), token1.clone()]);
+        let route = Route::direct(&pools, token1.clone(), token0.clone()).unwrap();
+        assert_eq!(route.pools, vec![deep]);
+        assert_eq!(
+            Route::direct(
+                std::slice::from_ref(&shallow),
+                token0.clone(),
+                token1.clone()
+            )
+            .unwrap()
+            .pools,
+            vec![shallow]
+        );
+        assert!(matches!(
+            Route::direct(&pools, token0.clone(), token2),
+            Err(Error::NoPoolForPair)
+        ));
+        assert!(matches!(
+            Route::direct(&[], token0, token1),
+            Err(Error::NoPoolForPair)
+        ));
+    }
+
+    #[test]
+    fn test_unwraps_to_native() {
+        let token0 = token!(1, "0x0000000000000000000000000000000000000001", 18, "t0");
+        let token1 = token!(1, "0x0000000000000000000000000000000000000002", 18, "t1");
+        let weth = WETH9::new().get(1).unwrap().clone();
+        let eth = Ether::on_chain(1);
+        let pools = vec![
+            full_range_pool(&token0, &token1, FeeAmount::MEDIUM, 1_000_000),
+            full_range_pool(&token1, &weth, FeeAmount::MEDIUM, 1_000_000),
+        ];
+
+        let route = Route::new(pools.clone(), token0.clone(), eth.clone()).unwrap();
+        assert_eq!(route.token_path.last(), Some(&weth));
+        assert!(route.unwraps_to_native());
+        let route = Route::new(pools[1..].to_vec(), token1.clone(), eth.clone()).unwrap();
+        assert!(route.unwraps_to_native());
+        assert!(!Route::new(pools, token0.clone(), weth.clone())
+            .unwrap()
+            .unwraps_to_native());
+        // ether input is wrapped, so it does not unwrap at the end
+        let route = Route::new(
+            vec![full_range_pool(
+                &weth,
+                &token0,
+                FeeAmount::MEDIUM,
+                1_000_000,
+            )],
+            eth,
+            token0,
+        )
+        .unwrap();
+        assert!(!route.unwraps_to_native());
+    }
+
+    #[test]
+    fn test_intermediate_tokens() {
+        let token0 = token!(1, "0x0000000000000000000000000000000000000001", 18, "t0");
+        let token1 = token!(1, "0x0000000000000000000000000000000000000002", 18, "t1");
+        let token2 = token!(1, "0x0000000000000000000000000000000000000003", 18, "t2");
+        let pool = |a: &Token, b: &Token| {
+            Pool::new(
+                a.clone(),
+                b.clone(),
+                FeeAmount::MEDIUM,
+                encode_sqrt_ratio_x96(1, 1),
+                0,
+                None,
+            )
+            .unwrap()
+        };
+
+        let route = Route::new(
+            vec![pool(&token0, &token1), pool(&token1, &token2)],
+            token0.clone(),
+            token2,
+        )
+        .unwrap();
+        assert_eq!(route.intermediate_tokens(), std::slice::from_ref(&token1));
+
+        let route = Route::new(vec![pool(&token0, &token1)], token0, token1).unwrap();
+        assert!(route.intermediate_tokens().is_empty());
+    }
+
+    #[test]
+    fn test_clone_shares_tick_data_providers() {
+        let token0 = token!(1, "0x0000000000000000000000000000000000000001", 18, "t0");
+        let token1 = token!(1, "0x0000000000000000000000000000000000000002", 18, "t1");
+        let route = Route::new(
+            vec![full_range_pool(
+                &token0,
+                &token1,
+                FeeAmount::MEDIUM,
+                1_000_000,
+            )],
+            token0,
+            token1,
+        )
+        .unwrap();
+        let strong_count = Arc::strong_count(&route.pools[0].tick_data_provider);
+
+        let cloned = route.clone();
+        assert!(Arc::ptr_eq(
+            &route.pools[0].tick_data_provider,
+            &cloned.pools[0].tick_data_provider
+        ));
+        assert_eq!(
+            Arc::strong_count(&route.pools[0].tick_data_provider),
+            strong_count + 1
+        );
+    }
+
+    #[test]
+    fn test_map_currencies() {
+        let eth = Ether::on_chain(1);
+        let weth = WETH9::new().get(1).unwrap().clone();
+        let token0 = token!(1, "0x0000000000000000000000000000000000000001", 18, "t0");
+        let pool_weth_0 = Pool::new(
+            weth.clone(),
+            token0.clone(),
+            FeeAmount::MEDIUM,
+            encode_sqrt_ratio_x96(1, 1),
+            0,
+            None,
+        )
+        .unwrap();
+        let route = Route::new(vec![pool_weth_0.clone()], eth, token0.clone()).unwrap();
+
+        let mapped = route
+            .clone()
+            .map_currencies(weth.clone(), token0.clone())
+            .unwrap();
+        assert!(!mapped.input.is_native());
+        assert_eq!(mapped.input, weth);
+        assert_eq!(mapped.pools, route.pools);
+        assert_eq!(mapped.token_path, route.token_path);
+
+        assert!(matches!(
+            route.map_currencies(token0.clone(), token0),
+            Err(Error::TokenNotInPool(0))
+        ));
+    }
+
+    #[test]
+    fn test_cycle_profit() {
+        let token0 = token!(1, "0x0000000000000000000000000000000000000001", 18, "t0");
+        let token1 = token!(1, "0x0000000000000000000000000000000000000002", 18, "t1");
+        let liquidity = 1_000_000_000_000_000_000u128;
+        let cheap = full_range_pool(&token0, &token1, FeeAmount::MEDIUM, liquidity);
+        // token1 is worth twice as much token0 in this pool
+        let expensive = Pool::new(
+            token0.clone(),
+            token1.clone(),
+            FeeAmount::LOW,
+            encode_sqrt_ratio_x96(1, 2),
+            liquidity,
+            Some(full_range_pool(&token0, &token1, FeeAmount::LOW, liquidity).tick_data_provider),
+        )
+        .unwrap();
+        let amount_in = CurrencyAmount::from_raw_amount(token0.clone(), 1000).unwrap();
+
+        let route = Route::new(
+            vec![cheap.clone(), expensive.clone()],
+            token0.clone(),
+            token0.clone(),
+        )
+        .unwrap();
+        assert!(route.is_cycle());
+        assert_eq!(
+            route.cycle_profit(amount_in.clone()).unwrap(),
+            I256::try_from(989).unwrap()
+        );
+
+        let route = Route::new(
+            vec![expensive, cheap.clone()],
+            token0.clone(),
+            token0.clone(),
+        )
+        .unwrap();
+        assert!(route.cycle_profit(amount_in).unwrap().is_negative());
+
+        let route = Route::new(vec![cheap], token0, token1).unwrap();
+        assert!(!route.is_cycle());
+    }
+
+    #[test]
+    fn test_is_simulatable() {
+        let token0 = token!(1, "0x0000000000000000000000000000000000000001", 18, "t0");
+        let token1 = token!(1, "0x0000000000000000000000000000000000000002", 18, "t1");
+        let token2 = token!(1, "0x0000000000000000000000000000000000000003", 18, "t2");
+        let pool_0_1 = full_range_pool(&token0, &token1, FeeAmount::MEDIUM, 1_000_000);
+        let pool_1_2 = Pool::new(
+            token1.clone(),
+            token2.clone(),
+            FeeAmount::MEDIUM,
+            encode_sqrt_ratio_x96(1, 1),
+            0,
+            None,
+        )
+        .unwrap();
+
+        let route = Route::new(vec![pool_0_1.clone()], token0.clone(), token1.clone()).unwrap();
+        assert!(route.is_simulatable());
+        let route = Route::new(vec![pool_0_1, pool_1_2], token0, token2).unwrap();
+        assert!(!route.is_simulatable());
+    }
+
+    #[test]
+    fn test_mid_price() {
+        let eth = Ether::on_chain(1);
+        let token0 = token!(1, "0x0000000000000000000000000000000000000001", 18, "t0");
+        let token1 = token!(1, "0x0000000000000000000000000000000000000002", 18, "t1");
+        let token2 = token!(1, "0x0000000000000000000000000000000000000003", 18, "t2");
+
+        let weth_instance = WETH9::new();
+        let weth = weth_instance.get(1).unwrap();
+
+        let pool_0_1 = Pool::new(
+            token0.clone(),
+            token1.clone(),
+            FeeAmount::MEDIUM,
+            encode_sqrt_ratio_x96(1, 5),
+            0,
+            None,
+        )
+        .unwrap();
+        let pool_1_2 = Pool::new(
+            token1.clone(),
+            token2.clone(),
+            FeeAmount::MEDIUM,
+            encode_sqrt_ratio_x96(15, 30),
+            0,
+            Some(Arc::new(TickListDataProvider::new(
+                vec![
+                    Tick::new(
+                        nearest_usable_tick(tick_math::MIN_TICK, FeeAmount::LOW.tick_spacing()),
+                        1,
+                        5,
+                    ),
+                    Tick::new(
+                        nearest_usable_tick(tick_math::MAX_TICK, FeeAmount::LOW.tick_spacing()),
+                        1,
+                        -5,
+                    ),
+                ],
+                FeeAmount::LOW.tick_spacing(),
+            ))),
+        )
+        .unwrap();
+        let pool_0_weth = Pool::new(
+            token0.clone(),
+            weth.clone(),
+            FeeAmount::MEDIUM,
+            encode_sqrt_ratio_x96(3, 1),
+            0,
+            None,
+        )
+        .unwrap();
+        let pool_1_weth = Pool::new(
+            token1.clone(),
+            weth.clone(),
+            FeeAmount::MEDIUM,
+            encode_sqrt_ratio_x96(1, 7),
+            0,
+            None,
+        )
+        .unwrap();
+
+        //IT CORRECT FOR 0 -> 1
+        let mut route = Route::new(vec![pool_0_1.clone()], token0.clone(), token1.clone()).unwrap();
+        let price = route.mid_price().unwrap();
+        assert_eq!(price.to_fixed(4, Rounding::RoundHalfUp), "0.2000");
+        assert!(price.meta.base_currency.equals(&token0));
+        assert!(price.meta.quote_currency.equals(&token1));
+
+        //IT IS CACHED
+        assert_eq!(route.mid_price().unwrap(), price);
+
+        //IT CORRECT FOR 1 -> 0
+        let price = Route::new(vec![pool_0_1.clone()], token1.clone(), token0.clone())
+            .unwrap()
+            .mid_price()
+            .unwrap();
+        assert_eq!(price.to_fixed(4, Rounding::RoundHalfUp), "5.0000");
+
+        //IT CORRECT FOR 0 -> 1 -> 2
+        let price = Route::new(
+            vec![pool_0_1.clone(), pool_1_2.clone()],
+            token0.clone(),
+            token2.clone(),
+        )
+        .unwrap()
+        .mid_price()
+        .unwrap();
+        assert_eq!(price.to_fixed(4, Rounding::RoundHalfUp), "0.1000");
+
+        //IT CORRECT FOR 2 -> 1 -> 0
+        let price = Route::new(
+            vec![pool_1_2.clone(), pool_0_1.clone()],
+            token2.clone(),
+            token0.clone(),
+        )
+        .unwrap()
+        .mid_price()
+        .unwrap();
+        assert_eq!(price.to_fixed(4, Rounding::RoundHalfUp), "10.0000");
+
+        //IT CORRECT FOR ETHER -> 0
+        let price = Route::new(vec![pool_0_weth.clone()], eth.clone(), token0.clone())
+            .unwrap()
+            .mid_price()
+            .unwrap();
+        assert_eq!(price.to_fixed(4, Rounding::RoundHalfUp), "0.3333");
+        assert!(price.meta.base_currency.equals(&eth));
+
+        //IT EQUALS THE WETH -> 0 PRICE FOR ETHER -> 0
+        let weth_price = Route::new(vec![pool_0_weth.clone()], weth.clone(), token0.clone())
+            .unwrap()
+            .mid_price()
+            .unwrap();
+        assert_eq!(price.as_fraction(), weth_price.as_fraction());
+        assert_eq!(price.meta.scalar, weth_price.meta.scalar);
+        assert_eq!(
+            price.adjusted_for_decimals(),
+            weth_price.adjusted_for_decimals()
+        );
+
+        //IT CORRECT FOR 1 -> WETH
+        let price = Route::new(vec![pool_1_weth.clone()], token1.clone(), weth.clone())
+            .unwrap()
+            .mid_price()
+            .unwrap();
+        assert_eq!(price.to_fixed(4, Rounding::RoundHalfUp), "0.1429");
+
+        //IT CORRECT FOR ETHER -> 0 -> 1 -> WETH
+        let price = Route::new(
+            vec![pool_0_weth, pool_0_1, pool_1_weth],
+            eth.clone(),
+            weth.clone(),
+        )
+        .unwrap()
+        .mid_price()
+        .unwrap();
+        assert_eq!(price.to_fixed(4, Rounding::RoundHalfUp), "0.0095");
+    }
+
+    #[test]
+    fn test_mid_price_input_not_in_first_pool() {
+        let token0 = token!(1, "0x0000000000000000000000000000000000000001", 18, "t0");
+        let token1 = token!(1, "0x0000000000000000000000000000000000000002", 18, "t1");
+        let token2 = token!(1, "0x0000000000000000000000000000000000000003", 18, "t2");
+        let pool_0_1 = Pool::new(
+            token0.clone(),
+            token1.clone(),
+            FeeAmount::MEDIUM,
+            encode_sqrt_ratio_x96(1, 5),
+            0,
+            None,
+        )
+        .unwrap();
+        let mut route = Route::new(vec![pool_0_1], token0, token1).unwrap();
+        // the public fields allow the input to drift from the first pool after construction
+        route.input = token2;
+        assert!(matches!(route.mid_price(), Err(Error::TokenNotInPool(0))));
+    }
+
+    #[test]
+    fn test_mid_price_through_zero_price_pool() {
+        let token0 = token!(1, "0x0000000000000000000000000000000000000001", 18, "t0");
+        let token1 = token!(1, "0x0000000000000000000000000000000000000002", 18, "t1");
+        let token2 = token!(1, "0x0000000000000000000000000000000000000003", 18, "t2");
+        let mut pool_1_2 = full_range_pool(&token1, &token2, FeeAmount::MEDIUM, 1_000_000);
+        pool_1_2.sqrt_ratio_x96 = U256::ZERO;
+        let pools = vec![
+            full_range_pool(&token0, &token1, FeeAmount::MEDIUM, 1_000_000),
+            pool_1_2,
+        ];
+
+        let mut route = Route::new(pools.clone(), token0.clone(), token2.clone()).unwrap();
+        assert!(matches!(route.mid_price(), Err(Error::DegeneratePrice)));
+        let mut route = Route::new(pools.into_iter().rev().collect(), token2, token0).unwrap();
+        assert!(matches!(route.mid_price(), Err(Error::DegeneratePrice)));
+        assert!(matches!(
+            route.mid_price_inverted(),
+            Err(Error::DegeneratePrice)
+        ));
+    }
+
+    #[test]
+    fn test_invalidate_mid_price() {
+        let token0 = token!(1, "0x0000000000000000000000000000000000000001", 18, "t0");
+        let token1 = token!(1, "0x0000000000000000000000000000000000000002", 18, "t1");
+        let pool_0_1 = Pool::new(
+            token0.clone(),
+            token1.clone(),
+            FeeAmount::MEDIUM,
+            encode_sqrt_ratio_x96(1, 5),
+            0,
+            None,
+        )
+        .unwrap();
+        let mut route = Route::new(vec![pool_0_1], token0.clone(), token1.clone()).unwrap();
+        assert_eq!(
+            route
+                .mid_price()
+                .unwrap()
+                .to_fixed(4, Rounding::RoundHalfUp),
+            "0.2000"
+        );
+
+        route.pools[0] = Pool::new(
+            token0,
+            token1,
+            FeeAmount::MEDIUM,
+            encode_sqrt_ratio_x96(1, 4),
+            0,
+            None,
+        )
+        .unwrap();
+        // the stale price is still cached
+        assert_eq!(
+            route
+                .mid_price()
+                .unwrap()
+                .to_fixed(4, Rounding::RoundHalfUp),
+            "0.2000"
+        );
+
+        route.invalidate_mid_price();
+        assert_eq!(
+            route
+                .mid_price()
+                .unwrap()
+                .to_fixed(4, Rounding::RoundHalfUp),
+            "0.2500"
+        );
+    }
+
+    #[test]
+    fn test_mid_price_in() {
+        let token0 = token!(1, "0x0000000000000000000000000000000000000001", 18, "t0");
+        let token1 = token!(1, "0x0000000000000000000000000000000000000002", 18, "t1");
+        let usdc = token!(1, "0x0000000000000000000000000000000000000003", 18, "usdc");
+        let pool_0_1 = Pool::new(
+            token0.clone(),
+            token1.clone(),
+            FeeAmount::MEDIUM,
+            encode_sqrt_ratio_x96(1, 5),
+            0,
+            None,
+        )
+        .unwrap();
+        let pool_1_usdc = Pool::new(
+            token1.clone(),
+            usdc.clone(),
+            FeeAmount::MEDIUM,
+            encode_sqrt_ratio_x96(4, 1),
+            0,
+            None,
+        )
+        .unwrap();
+        let mut route = Route::new(vec![pool_0_1.clone()], token0.clone(), token1).unwrap();
+
+        let price = route.mid_price_in(&pool_1_usdc, &usdc).unwrap();
+        assert!(price.meta.base_currency.equals(&token0));
+        assert!(price.meta.quote_currency.equals(&usdc));
+        assert_eq!(price.to_fixed(4, Rounding::RoundHalfUp), "0.8000");
+
+        assert!(matches!(
+            route.mid_price_in(&pool_0_1, &usdc),
+            Err(Error::InvolvesToken)
+        ));
+    }
+
+    #[test]
+    fn test_mid_price_inverted() {
+        let token0 = token!(1, "0x0000000000000000000000000000000000000001", 18, "t0");
+        let token1 = token!(1, "0x0000000000000000000000000000000000000002", 18, "t1");
+        let pool_0_1 = Pool::new(
+            token0.clone(),
+            token1.clone(),
+            FeeAmount::MEDIUM,
+            encode_sqrt_ratio_x96(1, 5),
+            0,
+            None,
+        )
+        .unwrap();
+        let mut route = Route::new(vec![pool_0_1], token0.clone(), token1.clone()).unwrap();
+        let price = route.mid_price().unwrap();
+        let inverted = route.mid_price_inverted().unwrap();
+
+        assert_eq!(inverted.numerator(), price.denominator());
+        assert_eq!(inverted.denominator(), price.numerator());
+        assert!(inverted.meta.base_currency.equals(&token1));
+        assert!(inverted.meta.quote_currency.equals(&token0));
+        assert_eq!(inverted.to_fixed(4, Rounding::RoundHalfUp), "5.0000");
     }
 }
@@ -1,6 +1,8 @@
 use crate::utils::{MAX_TICK, MIN_TICK};
 
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+/// An initialized tick. Ticks are ordered by `index` first, so a list of ticks can be sorted with
+/// `sort`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Tick {
     pub index: i32,
     pub liquidity_gross: u128,
@@ -55,4 +57,20 @@ mod tests {
     fn test_tick_above_max_tick() {
         Tick::new(MAX_TICK + 1, 0, 0);
     }
+
+    #[test]
+    fn test_sort_by_index() {
+        let mut ticks: Vec<Tick> = [
+            Tick::new(10, 1, -1),
+            Tick::new(-10, 5, 5),
+            Tick::new(0, 2, 0),
+            Tick::new(-20, 1, 1),
+        ]
+        .into();
+        ticks.sort();
+        assert_eq!(
+            ticks.iter().map(|tick| tick.index).collect::<Vec<_>>(),
+            vec![-20, -10, 0, 10]
+        );
+    }
 }
@@ -0,0 +1,139 @@
+use crate::{
+    entities::{Tick, TickDataProvider},
+    utils::{least_significant_bit, most_significant_bit, TickList},
+};
+use alloy_primitives::U256;
+use anyhow::Result;
+use num_integer::Integer;
+use std::collections::HashMap;
+
+/// A data provider for ticks that is backed by a word bitmap of the initialized ticks, mirroring
+/// the `TickBitmap` library of the core contracts, and a map from tick index to tick.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TickBitmapDataProvider {
+    bitmap: HashMap<i16, U256>,
+    ticks: HashMap<i32, Tick>,
+    tick_spacing: i32,
+}
+
+/// Computes the word and the bit within the word of a compressed tick
+fn position(compressed: i32) -> (i16, u8) {
+    ((compressed >> 8) as i16, (compressed & 0xff) as u8)
+}
+
+impl TickBitmapDataProvider {
+    pub fn new(ticks: Vec<Tick>, tick_spacing: i32) -> Self {
+        ticks.validate_list(tick_spacing);
+        let mut bitmap = HashMap::new();
+        for tick in &ticks {
+            let (word_pos, bit_pos) = position(tick.index / tick_spacing);
+            *bitmap.entry(word_pos).or_insert(U256::ZERO) |= U256::from(1) << bit_pos;
+        }
+        Self {
+            bitmap,
+            ticks: ticks.into_iter().map(|tick| (tick.index, tick)).collect(),
+            tick_spacing,
+        }
+    }
+
+    fn word(&self, word_pos: i16) -> U256 {
+        self.bitmap.get(&word_pos).copied().unwrap_or_default()
+    }
+}
+
+impl TickDataProvider for TickBitmapDataProvider {
+    type Tick = Tick;
+
+    fn get_tick(&self, tick: i32) -> Result<&Tick> {
+        Ok(self.ticks.get(&tick).expect("NOT_CONTAINED"))
+    }
+
+    fn next_initialized_tick_within_one_word(
+        &self,
+        tick: i32,
+        lte: bool,
+        tick_spacing: i32,
+    ) -> Result<(i32, bool)> {
+        assert_eq!(tick_spacing, self.tick_spacing, "TICK_SPACING");
+        let (compressed, _) = tick.div_mod_floor(&tick_spacing);
+        if lte {
+            let (word_pos, bit_pos) = position(compressed);
+            // all the 1s at or to the right of the current bit_pos
+            let mask = (U256::from(1) << bit_pos) - U256::from(1) + (U256::from(1) << bit_pos);
+            let masked = self.word(word_pos) & mask;
+            let initialized = !masked.is_zero();
+            let next = if initialized {
+                compressed - (bit_pos - most_significant_bit(masked)) as i32
+            } else {
+                compressed - bit_pos as i32
+            };
+            Ok((next * tick_spacing, initialized))
+        } else {
+            // start from the word of the next tick, since the current tick state doesn't matter
+            let (word_pos, bit_pos) = position(compressed + 1);
+            // all the 1s at or to the left of the bit_pos
+            let mask = !((U256::from(1) << bit_pos) - U256::from(1));
+            let masked = self.word(word_pos) & mask;
+            let initialized = !masked.is_zero();
+            let next = if initialized {
+                compressed + 1 + (least_significant_bit(masked) - bit_pos) as i32
+            } else {
+                compressed + 1 + (u8::MAX - bit_pos) as i32
+            };
+            Ok((next * tick_spacing, initialized))
+        }
+    }
+
+    fn has_ticks(&self) -> bool {
+        !self.ticks.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        entities::TickListDataProvider,
+        utils::{nearest_usable_tick, MAX_TICK, MIN_TICK},
+    };
+
+    #[test]
+    fn matches_tick_list_data_provider() {
+        let tick_spacing = 10;
+        let ticks = vec![
+            Tick::new(nearest_usable_tick(MIN_TICK, tick_spacing), 10, 10),
+            Tick::new(-2570, 5, 5),
+            Tick::new(-10, 3, 3),
+            Tick::new(0, 3, -3),
+            Tick::new(2550, 2, -2),
+            Tick::new(2560, 3, -3),
+            Tick::new(nearest_usable_tick(MAX_TICK, tick_spacing), 10, -10),
+        ];
+        let list = TickListDataProvider::new(ticks.clone(), tick_spacing);
+        let bitmap = TickBitmapDataProvider::new(ticks.clone(), tick_spacing);
+
+        for tick in (-8000..8000).step_by(7) {
+            for lte in [true, false] {
+                assert_eq!(
+                    bitmap
+                        .next_initialized_tick_within_one_word(tick, lte, tick_spacing)
+                        .unwrap(),
+                    list.next_initialized_tick_within_one_word(tick, lte, tick_spacing)
+                        .unwrap(),
+                    "tick {tick}, lte {lte}"
+                );
+            }
+        }
+        for tick in ticks {
+            assert_eq!(bitmap.get_tick(tick.index).unwrap(), &tick);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "NOT_CONTAINED")]
+    fn throws_if_tick_not_in_bitmap() {
+        TickBitmapDataProvider::new(vec![Tick::new(-1, 1, -1), Tick::new(1, 1, 1)], 1)
+            .get_tick(0)
+            .unwrap();
+    }
+}
@@ -1,6 +1,5 @@
-use crate::entities::Tick;
+use crate::{entities::Tick, error::Error};
 use anyhow::Result;
-use thiserror::Error;
 
 /// Provides information about ticks
 pub trait TickDataProvider {
@@ -32,11 +31,12 @@ pub trait TickDataProvider {
         lte: bool,
         tick_spacing: i32,
     ) -> Result<(i32, bool)>;
-}
 
-#[derive(Clone, Debug, Error)]
-#[error("No tick data provider was given")]
-pub struct NoTickDataError;
+    /// Whether the provider has any tick data to serve
+    fn has_ticks(&self) -> bool {
+        true
+    }
+}
 
 /// This tick data provider does not know how to fetch any tick data. It throws whenever it is required.
 /// Useful if you do not need to load tick data for your use case.
@@ -46,7 +46,7 @@ impl TickDataProvider for NoTickDataProvider {
     type Tick = Tick;
 
     fn get_tick(&self, _: i32) -> Result<&Tick> {
-        Err(NoTickDataError.into())
+        Err(Error::NoTickDataProvider.into())
     }
 
     fn next_initialized_tick_within_one_word(
@@ -55,7 +55,11 @@ impl TickDataProvider for NoTickDataProvider {
         _: bool,
         _: i32,
     ) -> Result<(i32, bool)> {
-        Err(NoTickDataError.into())
+        Err(Error::NoTickDataProvider.into())
+    }
+
+    fn has_ticks(&self) -> bool {
+        false
     }
 }
 
@@ -66,16 +70,17 @@ mod tests {
     #[test]
     fn test_no_tick_data_provider() {
         let tick_data_provider = NoTickDataProvider;
-        assert_eq!(
-            tick_data_provider.get_tick(0).unwrap_err().to_string(),
-            NoTickDataError.to_string()
-        );
-        assert_eq!(
+        assert!(matches!(
+            tick_data_provider.get_tick(0).unwrap_err().downcast_ref(),
+            Some(Error::NoTickDataProvider)
+        ));
+        assert!(matches!(
             tick_data_provider
                 .next_initialized_tick_within_one_word(0, false, 1)
                 .unwrap_err()
-                .to_string(),
-            NoTickDataError.to_string()
-        );
+                .downcast_ref(),
+            Some(Error::NoTickDataProvider)
+        ));
+        assert!(!tick_data_provider.has_ticks());
     }
 }
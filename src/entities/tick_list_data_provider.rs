@@ -1,17 +1,76 @@
 use crate::{
     entities::{Tick, TickDataProvider},
-    utils::TickList,
+    error::Error,
+    utils::{TickList, MAX_TICK, MIN_TICK},
 };
 use anyhow::Result;
 
 /// A data provider for ticks that is backed by an in-memory array of ticks.
-#[derive(Clone, Debug, Default)]
-pub struct TickListDataProvider(Vec<Tick>);
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TickListDataProvider(Vec<Tick>, i32);
 
 impl TickListDataProvider {
     pub fn new(ticks: Vec<Tick>, tick_spacing: i32) -> Self {
         ticks.validate_list(tick_spacing);
-        Self(ticks)
+        Self(ticks, tick_spacing)
+    }
+
+    /// Builds a provider from `(index, liquidity_gross, liquidity_net)` tuples in any order,
+    /// returning an error instead of panicking if they don't form a valid tick list
+    ///
+    /// # Arguments
+    ///
+    /// * `ticks`: The `(index, liquidity_gross, liquidity_net)` of each tick
+    /// * `tick_spacing`: The tick spacing of the pool
+    ///
+    /// returns: Result<Self, Error>
+    ///
+    pub fn from_tuples(ticks: Vec<(i32, u128, i128)>, tick_spacing: i32) -> Result<Self, Error> {
+        if tick_spacing <= 0 {
+            return Err(Error::InvalidTickSpacing(tick_spacing));
+        }
+        let mut provider = Self(Vec::with_capacity(ticks.len()), tick_spacing);
+        let mut liquidity_net = 0i128;
+        for (index, liquidity_gross, net) in ticks {
+            if !(MIN_TICK..=MAX_TICK).contains(&index) {
+                return Err(Error::TickOutOfRange(index));
+            }
+            if liquidity_gross < net.unsigned_abs() {
+                return Err(Error::InvalidLiquidityGross(index));
+            }
+            provider.insert_tick(Tick::new(index, liquidity_gross, net))?;
+            liquidity_net = liquidity_net
+                .checked_add(net)
+                .ok_or(Error::LiquidityOverflow)?;
+        }
+        if liquidity_net != 0 {
+            return Err(Error::NonZeroLiquidityNet);
+        }
+        Ok(provider)
+    }
+
+    /// Inserts a tick while keeping the list sorted, so that a provider can be built up as ticks
+    /// are loaded. A provider created with `Default` has no tick spacing and does not check the
+    /// alignment of the tick.
+    ///
+    /// # Arguments
+    ///
+    /// * `tick`: The tick to insert, which must not already be in the list and must be a multiple
+    ///   of the tick spacing
+    ///
+    /// returns: Result<(), Error>
+    ///
+    pub fn insert_tick(&mut self, tick: Tick) -> Result<(), Error> {
+        if self.1 != 0 && tick.index % self.1 != 0 {
+            return Err(Error::TickNotAligned(tick.index));
+        }
+        match self.0.binary_search_by_key(&tick.index, |t| t.index) {
+            Ok(_) => Err(Error::DuplicateTick(tick.index)),
+            Err(i) => {
+                self.0.insert(i, tick);
+                Ok(())
+            }
+        }
     }
 }
 
@@ -32,6 +91,10 @@ impl TickDataProvider for TickListDataProvider {
             .0
             .next_initialized_tick_within_one_word(tick, lte, tick_spacing))
     }
+
+    fn has_ticks(&self) -> bool {
+        !self.0.is_empty()
+    }
 }
 
 #[cfg(test)]
@@ -65,6 +128,63 @@ mod tests {
         PROVIDER.get_tick(0).unwrap();
     }
 
+    #[test]
+    fn from_tuples_matches_new() {
+        let provider = TickListDataProvider::from_tuples(vec![(1, 1, 1), (-1, 1, -1)], 1).unwrap();
+        assert_eq!(provider, *PROVIDER);
+        assert!(matches!(
+            TickListDataProvider::from_tuples(vec![(-1, 1, -1), (1, 2, 2)], 1),
+            Err(Error::NonZeroLiquidityNet)
+        ));
+        assert!(matches!(
+            TickListDataProvider::from_tuples(vec![(-1, 1, -1), (5, 1, 1)], 10),
+            Err(Error::TickNotAligned(-1))
+        ));
+    }
+
+    #[test]
+    fn from_tuples_errors_instead_of_panicking() {
+        assert!(matches!(
+            TickListDataProvider::from_tuples(vec![], 0),
+            Err(Error::InvalidTickSpacing(0))
+        ));
+        assert!(matches!(
+            TickListDataProvider::from_tuples(vec![(MIN_TICK - 1, 1, 1), (1, 1, -1)], 1),
+            Err(Error::TickOutOfRange(t)) if t == MIN_TICK - 1
+        ));
+        assert!(matches!(
+            TickListDataProvider::from_tuples(vec![(-1, 1, -2), (1, 2, 2)], 1),
+            Err(Error::InvalidLiquidityGross(-1))
+        ));
+        assert!(matches!(
+            TickListDataProvider::from_tuples(
+                vec![(-1, u128::MAX, i128::MAX), (1, u128::MAX, i128::MAX)],
+                1
+            ),
+            Err(Error::LiquidityOverflow)
+        ));
+    }
+
+    #[test]
+    fn insert_tick_keeps_the_list_sorted() {
+        let mut provider = TickListDataProvider::new(vec![], 10);
+        for index in [20, -10, 0, -30] {
+            provider.insert_tick(Tick::new(index, 1, 0)).unwrap();
+        }
+        assert_eq!(
+            provider.0.iter().map(|tick| tick.index).collect::<Vec<_>>(),
+            [-30, -10, 0, 20]
+        );
+        assert!(matches!(
+            provider.insert_tick(Tick::new(0, 1, 0)),
+            Err(Error::DuplicateTick(0))
+        ));
+        assert!(matches!(
+            provider.insert_tick(Tick::new(5, 1, 0)),
+            Err(Error::TickNotAligned(5))
+        ));
+    }
+
     #[test]
     fn gets_the_smallest_tick_from_the_list() {
         let tick = PROVIDER.get_tick(-1).unwrap();
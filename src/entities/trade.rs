@@ -0,0 +1,2049 @@
+use super::pool::is_skippable_pool_error;
+use crate::{error::Error, prelude::*};
+use alloy_primitives::{Address, U256};
+use anyhow::Result;
+use num_traits::Zero;
+use std::{
+    collections::HashSet,
+    hash::{Hash, Hasher},
+};
+use uniswap_sdk_core::{prelude::*, utils::sorted_insert::sorted_insert};
+
+/// A swap through a single route, with the amounts going in and out
+#[derive(Clone, Debug)]
+pub struct Swap<TInput, TOutput>
+where
+    TInput: CurrencyTrait,
+    TOutput: CurrencyTrait,
+{
+    pub route: Route<TInput, TOutput>,
+    pub input_amount: CurrencyAmount<TInput>,
+    pub output_amount: CurrencyAmount<TOutput>,
+}
+
+/// Options for finding the best trades through a set of pools
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BestTradeOptions {
+    /// How many results to return, 3 by default
+    pub max_num_results: Option<usize>,
+    /// The maximum number of hops a trade should contain, [`MAX_HOPS`] by default
+    pub max_hops: Option<usize>,
+}
+
+/// Trades comparator, an extension of the input output comparator that also considers other
+/// dimensions of the trade in ranking them
+///
+/// # Arguments
+///
+/// * `a`: The first trade to compare
+/// * `b`: The second trade to compare
+///
+/// returns: Ordering
+///
+pub fn trade_comparator<TInput, TOutput>(
+    a: &Trade<TInput, TOutput>,
+    b: &Trade<TInput, TOutput>,
+) -> Ordering
+where
+    TInput: CurrencyTrait,
+    TOutput: CurrencyTrait,
+{
+    let (a_input, a_output) = (a.total_input_amount(), a.total_output_amount());
+    let (b_input, b_output) = (b.total_input_amount(), b.total_output_amount());
+    assert!(
+        a_input.meta.currency.equals(&b_input.meta.currency),
+        "INPUT_CURRENCY"
+    );
+    assert!(
+        a_output.meta.currency.equals(&b_output.meta.currency),
+        "OUTPUT_CURRENCY"
+    );
+    let (a_input, a_output) = (a_input.as_fraction(), a_output.as_fraction());
+    let (b_input, b_output) = (b_input.as_fraction(), b_output.as_fraction());
+    if a_output == b_output {
+        if a_input == b_input {
+            // consider the number of hops since each hop costs gas
+            let hops = |trade: &Trade<TInput, TOutput>| {
+                trade
+                    .swaps
+                    .iter()
+                    .map(|swap| swap.route.token_path.len())
+                    .sum::<usize>()
+            };
+            return hops(a).cmp(&hops(b));
+        }
+        // trade A requires less input than trade B, so A should come first
+        if a_input < b_input {
+            Ordering::Less
+        } else {
+            Ordering::Greater
+        }
+    } else if a_output < b_output {
+        Ordering::Greater
+    } else {
+        Ordering::Less
+    }
+}
+
+/// Represents a trade executed against a set of routes where some percentage of the input is split
+/// across each route.
+///
+/// Each route has its own set of pools. Pools can not be re-used across routes.
+///
+/// Does not account for slippage, i.e., changes in price environment that can occur between the time
+/// the trade is submitted and when it is executed.
+#[derive(Clone, Debug)]
+pub struct Trade<TInput, TOutput>
+where
+    TInput: CurrencyTrait,
+    TOutput: CurrencyTrait,
+{
+    /// The swaps of the trade, i.e. which routes and how much is swapped in each that make up the
+    /// trade.
+    pub swaps: Vec<Swap<TInput, TOutput>>,
+    /// The type of the trade, either exact in or exact out.
+    pub trade_type: TradeType,
+    _input_amount: Option<CurrencyAmount<TInput>>,
+    _output_amount: Option<CurrencyAmount<TOutput>>,
+    _execution_price: Option<Price<TInput, TOutput>>,
+    _price_impact: Option<Percent>,
+}
+
+/// The structural identity of a swap: the native flags of the route ends, the token path, the pool
+/// fees and the amounts in and out
+type SwapKey = (bool, bool, Vec<Address>, Vec<FeeAmount>, BigInt, BigInt);
+
+impl<TInput, TOutput> Trade<TInput, TOutput>
+where
+    TInput: CurrencyTrait,
+    TOutput: CurrencyTrait,
+{
+    fn key(&self) -> (TradeType, Vec<SwapKey>) {
+        let swaps = self
+            .swaps
+            .iter()
+            .map(|swap| {
+                (
+                    swap.route.input.is_native(),
+                    swap.route.output.is_native(),
+                    swap.route
+                        .token_path
+                        .iter()
+                        .map(|token| token.address())
+                        .collect(),
+                    swap.route.pools.iter().map(|pool| pool.fee).collect(),
+                    swap.input_amount.quotient(),
+                    swap.output_amount.quotient(),
+                )
+            })
+            .collect();
+        (self.trade_type, swaps)
+    }
+
+    /// The token paths and pool fees of the routes of the trade
+    fn route_key(&self) -> Vec<(Vec<Address>, Vec<FeeAmount>)> {
+        self.swaps
+            .iter()
+            .map(|swap| {
+                (
+                    swap.route
+                        .token_path
+                        .iter()
+                        .map(|token| token.address())
+                        .collect(),
+                    swap.route.pools.iter().map(|pool| pool.fee).collect(),
+                )
+            })
+            .collect()
+    }
+
+    /// Inserts a trade found by the path finders into the sorted best trades, unless a trade over
+    /// the same token path and fee tiers ranks at least as high, in which case it is dropped
+    fn insert_best_trade(
+        best_trades: &mut Vec<Self>,
+        trade: Self,
+        max_num_results: usize,
+    ) -> Result<()> {
+        let route_key = trade.route_key();
+        if let Some(i) = best_trades
+            .iter()
+            .position(|best_trade| best_trade.route_key() == route_key)
+        {
+            if trade_comparator(&trade, &best_trades[i]) != Ordering::Less {
+                return Ok(());
+            }
+            best_trades.remove(i);
+        }
+        sorted_insert(best_trades, trade, max_num_results, trade_comparator)?;
+        Ok(())
+    }
+}
+
+/// Trades are equal if they swap the same amounts through the same token paths and fee tiers, so
+/// that duplicates found by the path finders can be removed with a `HashSet`. The pool states are
+/// not compared.
+impl<TInput, TOutput> PartialEq for Trade<TInput, TOutput>
+where
+    TInput: CurrencyTrait,
+    TOutput: CurrencyTrait,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl<TInput, TOutput> Eq for Trade<TInput, TOutput>
+where
+    TInput: CurrencyTrait,
+    TOutput: CurrencyTrait,
+{
+}
+
+impl<TInput, TOutput> Hash for Trade<TInput, TOutput>
+where
+    TInput: CurrencyTrait,
+    TOutput: CurrencyTrait,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key().hash(state);
+    }
+}
+
+impl<TInput, TOutput> Trade<TInput, TOutput>
+where
+    TInput: CurrencyTrait,
+    TOutput: CurrencyTrait,
+{
+    /// Construct a trade by passing in the pre-computed property values
+    ///
+    /// # Arguments
+    ///
+    /// * `swaps`: The routes through which the trade occurs
+    /// * `trade_type`: The type of trade, exact input or exact output
+    ///
+    /// returns: Trade, or [`Error::ZeroAmount`] if the input amount of a swap is zero, which
+    /// would leave the execution price undefined
+    ///
+    fn new(swaps: Vec<Swap<TInput, TOutput>>, trade_type: TradeType) -> Result<Self> {
+        if swaps
+            .iter()
+            .any(|swap| swap.input_amount.quotient().is_zero())
+        {
+            return Err(Error::ZeroAmount.into());
+        }
+        let input_currency = swaps[0].input_amount.meta.currency.wrapped();
+        let output_currency = swaps[0].output_amount.meta.currency.wrapped();
+        for Swap { route, .. } in &swaps {
+            assert!(
+                input_currency.equals(&route.input.wrapped()),
+                "INPUT_CURRENCY_MATCH"
+            );
+            assert!(
+                output_currency.equals(&route.output.wrapped()),
+                "OUTPUT_CURRENCY_MATCH"
+            );
+        }
+        let num_pools = swaps
+            .iter()
+            .map(|swap| swap.route.pools.len())
+            .sum::<usize>();
+        let pool_address_set = swaps
+            .iter()
+            .flat_map(|swap| swap.route.pools.iter())
+            .map(Pool::address)
+            .collect::<HashSet<Address>>();
+        assert_eq!(num_pools, pool_address_set.len(), "POOLS_DUPLICATED");
+        Ok(Self {
+            swaps,
+            trade_type,
+            _input_amount: None,
+            _output_amount: None,
+            _execution_price: None,
+            _price_impact: None,
+        })
+    }
+
+    /// Creates a trade without computing the result of swapping through the route.
+    /// Useful when you have simulated the trade elsewhere and do not have any tick data
+    ///
+    /// # Arguments
+    ///
+    /// * `route`: The route to swap through
+    /// * `input_amount`: The amount of input currency
+    /// * `output_amount`: The amount of output currency
+    /// * `trade_type`: The type of the trade, either exact in or exact out
+    ///
+    /// returns: Trade
+    ///
+    pub fn create_unchecked_trade(
+        route: Route<TInput, TOutput>,
+        input_amount: CurrencyAmount<TInput>,
+        output_amount: CurrencyAmount<TOutput>,
+        trade_type: TradeType,
+    ) -> Result<Self> {
+        Self::new(
+            vec![Swap {
+                route,
+                input_amount,
+                output_amount,
+            }],
+            trade_type,
+        )
+    }
+
+    /// Creates a trade without computing the result of swapping through the routes.
+    /// Useful when you have simulated the trade elsewhere and do not have any tick data
+    ///
+    /// # Arguments
+    ///
+    /// * `swaps`: The routes to swap through and how much of the amount should be routed through each
+    /// * `trade_type`: The type of the trade, either exact in or exact out
+    ///
+    /// returns: Trade
+    ///
+    pub fn create_unchecked_trade_with_multiple_routes(
+        swaps: Vec<Swap<TInput, TOutput>>,
+        trade_type: TradeType,
+    ) -> Result<Self> {
+        Self::new(swaps, trade_type)
+    }
+
+    /// Constructs an exact in trade with the given amount in and route
+    ///
+    /// # Arguments
+    ///
+    /// * `route`: The route of the exact in trade
+    /// * `amount_in`: The amount being passed in
+    ///
+    /// returns: Trade
+    ///
+    pub fn exact_in(
+        route: Route<TInput, TOutput>,
+        amount_in: CurrencyAmount<TInput>,
+    ) -> Result<Self> {
+        Self::from_route(route, amount_in, TradeType::ExactInput)
+    }
+
+    /// Constructs an exact out trade with the given amount out and route
+    ///
+    /// # Arguments
+    ///
+    /// * `route`: The route of the exact out trade
+    /// * `amount_out`: The amount returned by the trade
+    ///
+    /// returns: Trade
+    ///
+    pub fn exact_out(
+        route: Route<TInput, TOutput>,
+        amount_out: CurrencyAmount<TOutput>,
+    ) -> Result<Self> {
+        Self::from_route(route, amount_out, TradeType::ExactOutput)
+    }
+
+    /// Constructs a trade by simulating swaps through the given route
+    ///
+    /// # Arguments
+    ///
+    /// * `route`: The route to swap through
+    /// * `amount`: The amount specified, either input or output, depending on `trade_type`
+    /// * `trade_type`: Whether the trade is an exact input or exact output swap
+    ///
+    /// returns: Trade
+    ///
+    pub fn from_route(
+        route: Route<TInput, TOutput>,
+        amount: CurrencyAmount<impl CurrencyTrait>,
+        trade_type: TradeType,
+    ) -> Result<Self> {
+        if amount.quotient().is_zero() {
+            return Err(Error::ZeroAmount.into());
+        }
+        if route.spans_chains() {
+            return Err(Error::ChainIdIsDifferent.into());
+        }
+        let mut token_amount: CurrencyAmount<Token> = amount.wrapped()?;
+        let input_amount: CurrencyAmount<TInput>;
+        let output_amount: CurrencyAmount<TOutput>;
+        match trade_type {
+            TradeType::ExactInput => {
+                assert!(amount.meta.currency.equals(&route.input), "INPUT");
+                for pool in &route.pools {
+                    (token_amount, _) = pool.get_output_amount(token_amount, None)?;
+                }
+                input_amount = CurrencyAmount::from_fractional_amount(
+                    route.input.clone(),
+                    amount.numerator(),
+                    amount.denominator(),
+                )?;
+                output_amount = CurrencyAmount::from_fractional_amount(
+                    route.output.clone(),
+                    token_amount.numerator(),
+                    token_amount.denominator(),
+                )?;
+            }
+            TradeType::ExactOutput => {
+                assert!(amount.meta.currency.equals(&route.output), "OUTPUT");
+                for pool in route.pools.iter().rev() {
+                    (token_amount, _) = pool.get_input_amount(token_amount, None)?;
+                }
+                input_amount = CurrencyAmount::from_fractional_amount(
+                    route.input.clone(),
+                    token_amount.numerator(),
+                    token_amount.denominator(),
+                )?;
+                output_amount = CurrencyAmount::from_fractional_amount(
+                    route.output.clone(),
+                    amount.numerator(),
+                    amount.denominator(),
+                )?;
+            }
+        }
+        Self::new(
+            vec![Swap {
+                route,
+                input_amount,
+                output_amount,
+            }],
+            trade_type,
+        )
+    }
+
+    /// Constructs a trade from the result of the on-chain quoter instead of simulating the swaps
+    /// locally, so that the trade matches the quote exactly
+    ///
+    /// # Arguments
+    ///
+    /// * `route`: The route that was quoted
+    /// * `amount`: The amount specified, either input or output, depending on `trade_type`
+    /// * `trade_type`: Whether the trade is an exact input or exact output swap
+    /// * `quoter_amount`: The amount returned by the quoter, the output amount of an exact input
+    ///   trade or the input amount of an exact output trade
+    ///
+    /// returns: Trade
+    ///
+    pub fn from_quoter_result(
+        route: Route<TInput, TOutput>,
+        amount: CurrencyAmount<impl CurrencyTrait>,
+        trade_type: TradeType,
+        quoter_amount: U256,
+    ) -> Result<Self> {
+        if amount.quotient().is_zero() {
+            return Err(Error::ZeroAmount.into());
+        }
+        let input_amount: CurrencyAmount<TInput>;
+        let output_amount: CurrencyAmount<TOutput>;
+        match trade_type {
+            TradeType::ExactInput => {
+                assert!(amount.meta.currency.equals(&route.input), "INPUT");
+                input_amount = CurrencyAmount::from_fractional_amount(
+                    route.input.clone(),
+                    amount.numerator(),
+                    amount.denominator(),
+                )?;
+                output_amount = CurrencyAmount::from_raw_amount(
+                    route.output.clone(),
+                    u256_to_big_int(quoter_amount),
+                )?;
+            }
+            TradeType::ExactOutput => {
+                assert!(amount.meta.currency.equals(&route.output), "OUTPUT");
+                input_amount = CurrencyAmount::from_raw_amount(
+                    route.input.clone(),
+                    u256_to_big_int(quoter_amount),
+                )?;
+                output_amount = CurrencyAmount::from_fractional_amount(
+                    route.output.clone(),
+                    amount.numerator(),
+                    amount.denominator(),
+                )?;
+            }
+        }
+        Self::create_unchecked_trade(route, input_amount, output_amount, trade_type)
+    }
+
+    /// Constructs a trade from routes by simulating swaps
+    ///
+    /// # Arguments
+    ///
+    /// * `routes`: The routes to swap through and how much of the amount should be routed through each
+    /// * `trade_type`: Whether the trade is an exact input or exact output swap
+    ///
+    /// returns: Trade
+    ///
+    pub fn from_routes(
+        routes: Vec<(CurrencyAmount<impl CurrencyTrait>, Route<TInput, TOutput>)>,
+        trade_type: TradeType,
+    ) -> Result<Self> {
+        let mut swaps = Vec::with_capacity(routes.len());
+        for (amount, route) in routes {
+            let trade = Self::from_route(route, amount, trade_type)?;
+            swaps.extend(trade.swaps);
+        }
+        Self::new(swaps, trade_type)
+    }
+
+    /// Splits an exact input amount between two routes and returns the split that maximizes the
+    /// output amount. Split ratios are scanned in `1 / steps` increments, including sending the
+    /// whole amount through either route. The routes must not share any pools.
+    ///
+    /// # Arguments
+    ///
+    /// * `route_a`: The first route to swap through
+    /// * `route_b`: The second route to swap through
+    /// * `amount_in`: The total amount being passed in
+    /// * `steps`: The number of increments in which the split ratio is scanned
+    ///
+    /// returns: Trade
+    ///
+    pub fn best_split_two(
+        route_a: Route<TInput, TOutput>,
+        route_b: Route<TInput, TOutput>,
+        amount_in: CurrencyAmount<TInput>,
+        steps: usize,
+    ) -> Result<Self> {
+        assert!(steps > 0, "STEPS");
+        let total = amount_in.quotient();
+        let mut best: Option<(BigInt, Self)> = None;
+        for i in 0..=steps {
+            let amount_a = &total * i / steps;
+            let amount_b = &total - &amount_a;
+            let mut routes = Vec::with_capacity(2);
+            if !amount_a.is_zero() {
+                routes.push((
+                    CurrencyAmount::from_raw_amount(amount_in.meta.currency.clone(), amount_a)?,
+                    route_a.clone(),
+                ));
+            }
+            if !amount_b.is_zero() {
+                routes.push((
+                    CurrencyAmount::from_raw_amount(amount_in.meta.currency.clone(), amount_b)?,
+                    route_b.clone(),
+                ));
+            }
+            let mut trade = Self::from_routes(routes, TradeType::ExactInput)?;
+            let output = trade.output_amount()?.quotient();
+            let is_better = match &best {
+                Some((best_output, _)) => output > *best_output,
+                None => true,
+            };
+            if is_better {
+                best = Some((output, trade));
+            }
+        }
+        Ok(best.unwrap().1)
+    }
+
+    /// Given a list of pools, and a fixed amount in, returns the top `max_num_results` trades that
+    /// go from an input token amount to an output token, making at most `max_hops` hops.
+    /// Note this does not consider aggregation, as routes are linear. It's possible a better route
+    /// exists by splitting the amount in among multiple routes.
+    ///
+    /// # Arguments
+    ///
+    /// * `pools`: The pools to consider in finding the best trade
+    /// * `currency_amount_in`: The exact amount of input currency to spend
+    /// * `currency_out`: The desired currency out
+    /// * `best_trade_options`: Maximum number of results to return and maximum number of hops a
+    ///   returned trade can make, which may not exceed [`MAX_HOPS`]
+    /// * `current_pools`: Used in recursion; the current list of pools
+    /// * `next_amount_in`: Used in recursion; the original value of the currency_amount_in
+    ///   parameter
+    /// * `best_trades`: Used in recursion; the current list of best trades
+    ///
+    /// returns: The best trades, or [`Error::MaxHopsExceeded`] if `max_hops` exceeds [`MAX_HOPS`]
+    ///
+    pub fn best_trade_exact_in<'a>(
+        pools: Vec<Pool>,
+        currency_amount_in: &CurrencyAmount<TInput>,
+        currency_out: &TOutput,
+        best_trade_options: BestTradeOptions,
+        current_pools: Vec<Pool>,
+        next_amount_in: Option<CurrencyAmount<Token>>,
+        best_trades: &'a mut Vec<Self>,
+    ) -> Result<&'a mut Vec<Self>> {
+        assert!(!pools.is_empty(), "POOLS");
+        let max_num_results = best_trade_options.max_num_results.unwrap_or(3);
+        let max_hops = best_trade_options.max_hops.unwrap_or(MAX_HOPS);
+        if max_hops > MAX_HOPS {
+            return Err(Error::MaxHopsExceeded.into());
+        }
+        assert!(max_hops > 0, "MAX_HOPS");
+        assert!(
+            next_amount_in.is_none() || !current_pools.is_empty(),
+            "INVALID_RECURSION"
+        );
+        let amount_in = match next_amount_in {
+            Some(amount_in) => amount_in,
+            None => currency_amount_in.wrapped()?,
+        };
+        let token_out = currency_out.wrapped();
+        for (i, pool) in pools.iter().enumerate() {
+            // pool irrelevant
+            if !pool.involves_token(&amount_in.meta.currency) {
+                continue;
+            }
+            let amount_out = match pool.get_output_amount(amount_in.clone(), None) {
+                // the pool has insufficient liquidity for the input
+                Ok((amount_out, _)) if amount_out.quotient().is_zero() => continue,
+                Ok((amount_out, _)) => amount_out,
+                Err(err) if is_skippable_pool_error(&err) => continue,
+                Err(err) => return Err(err),
+            };
+            let mut next_pools = current_pools.clone();
+            next_pools.push(pool.clone());
+            // we have arrived at the output token, so this is the final trade of one of the paths
+            if amount_out.meta.currency.equals(&token_out) {
+                let trade = Self::from_route(
+                    Route::new(
+                        next_pools,
+                        currency_amount_in.meta.currency.clone(),
+                        currency_out.clone(),
+                    )?,
+                    currency_amount_in.clone(),
+                    TradeType::ExactInput,
+                )?;
+                Self::insert_best_trade(best_trades, trade, max_num_results)?;
+            } else if max_hops > 1 && pools.len() > 1 {
+                let pools_excluding_this_pool =
+                    pools[..i].iter().chain(&pools[i + 1..]).cloned().collect();
+                // otherwise, consider all the other paths that lead from this token as long as we
+                // have not exceeded maxHops
+                Self::best_trade_exact_in(
+                    pools_excluding_this_pool,
+                    currency_amount_in,
+                    currency_out,
+                    BestTradeOptions {
+                        max_num_results: Some(max_num_results),
+                        max_hops: Some(max_hops - 1),
+                    },
+                    next_pools,
+                    Some(amount_out),
+                    best_trades,
+                )?;
+            }
+        }
+        Ok(best_trades)
+    }
+
+    /// Similar to [`Trade::best_trade_exact_in`] but instead targets a fixed output amount.
+    /// Given a list of pools, and a fixed amount out, returns the top `max_num_results` trades that
+    /// go from an input token to an output token amount, making at most `max_hops` hops.
+    ///
+    /// # Arguments
+    ///
+    /// * `pools`: The pools to consider in finding the best trade
+    /// * `currency_in`: The currency to spend
+    /// * `currency_amount_out`: The desired currency amount out
+    /// * `best_trade_options`: Maximum number of results to return and maximum number of hops a
+    ///   returned trade can make, which may not exceed [`MAX_HOPS`]
+    /// * `current_pools`: Used in recursion; the current list of pools
+    /// * `next_amount_out`: Used in recursion; the exact amount of currency out
+    /// * `best_trades`: Used in recursion; the current list of best trades
+    ///
+    /// returns: The best trades, or [`Error::MaxHopsExceeded`] if `max_hops` exceeds [`MAX_HOPS`]
+    ///
+    pub fn best_trade_exact_out<'a>(
+        pools: Vec<Pool>,
+        currency_in: &TInput,
+        currency_amount_out: &CurrencyAmount<TOutput>,
+        best_trade_options: BestTradeOptions,
+        current_pools: Vec<Pool>,
+        next_amount_out: Option<CurrencyAmount<Token>>,
+        best_trades: &'a mut Vec<Self>,
+    ) -> Result<&'a mut Vec<Self>> {
+        assert!(!pools.is_empty(), "POOLS");
+        let max_num_results = best_trade_options.max_num_results.unwrap_or(3);
+        let max_hops = best_trade_options.max_hops.unwrap_or(MAX_HOPS);
+        if max_hops > MAX_HOPS {
+            return Err(Error::MaxHopsExceeded.into());
+        }
+        assert!(max_hops > 0, "MAX_HOPS");
+        assert!(
+            next_amount_out.is_none() || !current_pools.is_empty(),
+            "INVALID_RECURSION"
+        );
+        let amount_out = match next_amount_out {
+            Some(amount_out) => amount_out,
+            None => currency_amount_out.wrapped()?,
+        };
+        let token_in = currency_in.wrapped();
+        for (i, pool) in pools.iter().enumerate() {
+            // pool irrelevant
+            if !pool.involves_token(&amount_out.meta.currency) {
+                continue;
+            }
+            let amount_in = match pool.get_input_amount(amount_out.clone(), None) {
+                Ok((amount_in, _)) => amount_in,
+                Err(err) if is_skippable_pool_error(&err) => continue,
+                Err(err) => return Err(err),
+            };
+            let mut next_pools = vec![pool.clone()];
+            next_pools.extend(current_pools.iter().cloned());
+            // we have arrived at the input token, so this is the first trade of one of the paths
+            if amount_in.meta.currency.equals(&token_in) {
+                let trade = Self::from_route(
+                    Route::new(
+                        next_pools,
+                        currency_in.clone(),
+                        currency_amount_out.meta.currency.clone(),
+                    )?,
+                    currency_amount_out.clone(),
+                    TradeType::ExactOutput,
+                )?;
+                Self::insert_best_trade(best_trades, trade, max_num_results)?;
+            } else if max_hops > 1 && pools.len() > 1 {
+                let pools_excluding_this_pool =
+                    pools[..i].iter().chain(&pools[i + 1..]).cloned().collect();
+                // otherwise, consider all the other paths that arrive at this token as long as we
+                // have not exceeded maxHops
+                Self::best_trade_exact_out(
+                    pools_excluding_this_pool,
+                    currency_in,
+                    currency_amount_out,
+                    BestTradeOptions {
+                        max_num_results: Some(max_num_results),
+                        max_hops: Some(max_hops - 1),
+                    },
+                    next_pools,
+                    Some(amount_in),
+                    best_trades,
+                )?;
+            }
+        }
+        Ok(best_trades)
+    }
+
+    /// When the trade consists of just a single route, this returns the route of the trade.
+    pub fn route(&self) -> &Route<TInput, TOutput> {
+        assert_eq!(self.swaps.len(), 1, "MULTIPLE_ROUTES");
+        &self.swaps[0].route
+    }
+
+    /// The input amount for the trade assuming no slippage.
+    pub fn input_amount(&mut self) -> Result<CurrencyAmount<TInput>> {
+        if self._input_amount.is_none() {
+            self._input_amount = Some(self.total_input_amount());
+        }
+        Ok(self._input_amount.clone().unwrap())
+    }
+
+    /// The output amount for the trade assuming no slippage.
+    pub fn output_amount(&mut self) -> Result<CurrencyAmount<TOutput>> {
+        if self._output_amount.is_none() {
+            self._output_amount = Some(self.total_output_amount());
+        }
+        Ok(self._output_amount.clone().unwrap())
+    }
+
+    fn total_input_amount(&self) -> CurrencyAmount<TInput> {
+        self.swaps
+            .iter()
+            .skip(1)
+            .fold(self.swaps[0].input_amount.clone(), |total, swap| {
+                total
+                    .add(&swap.input_amount)
+                    .expect("swaps share the input currency")
+            })
+    }
+
+    fn total_output_amount(&self) -> CurrencyAmount<TOutput> {
+        self.swaps
+            .iter()
+            .skip(1)
+            .fold(self.swaps[0].output_amount.clone(), |total, swap| {
+                total
+                    .add(&swap.output_amount)
+                    .expect("swaps share the output currency")
+            })
+    }
+
+    /// The price expressed in terms of output amount/input amount.
+    pub fn execution_price(&mut self) -> Result<Price<TInput, TOutput>> {
+        if self._execution_price.is_none() {
+            let input_amount = self.input_amount()?;
+            let output_amount = self.output_amount()?;
+            self._execution_price = Some(Price::new(
+                input_amount.meta.currency.clone(),
+                output_amount.meta.currency.clone(),
+                input_amount.quotient(),
+                output_amount.quotient(),
+            ));
+        }
+        Ok(self._execution_price.clone().unwrap())
+    }
+
+    /// The only swap of a single route trade, or [`Error::MultipleRoutes`] instead of the panic
+    /// of [`Trade::route`]
+    fn single_swap(&self) -> Result<&Swap<TInput, TOutput>, Error> {
+        match self.swaps.as_slice() {
+            [swap] => Ok(swap),
+            _ => Err(Error::MultipleRoutes),
+        }
+    }
+
+    /// The amounts along the trade's route from simulating it hop by hop, starting with the
+    /// input amount and ending with the output amount
+    fn hop_amounts(&self) -> Result<Vec<CurrencyAmount<Token>>> {
+        let swap = self.single_swap()?;
+        let route = &swap.route;
+        let mut amounts = Vec::with_capacity(route.pools.len() + 1);
+        match self.trade_type {
+            TradeType::ExactInput => {
+                amounts.push(swap.input_amount.wrapped()?);
+                for pool in &route.pools {
+                    let (amount_out, _) =
+                        pool.get_output_amount(amounts.last().unwrap().clone(), None)?;
+                    amounts.push(amount_out);
+                }
+            }
+            TradeType::ExactOutput => {
+                amounts.push(swap.output_amount.wrapped()?);
+                for pool in route.pools.iter().rev() {
+                    let (amount_in, _) =
+                        pool.get_input_amount(amounts.last().unwrap().clone(), None)?;
+                    amounts.push(amount_in);
+                }
+                amounts.reverse();
+            }
+        }
+        Ok(amounts)
+    }
+
+    /// The LP fee taken at each hop of the trade's route, i.e. the input amount of the hop times the
+    /// fee of its pool, rounded down
+    ///
+    /// returns: The fee of each hop in the input token of the hop, in the order of the route, or
+    /// [`Error::MultipleRoutes`] if the trade has more than one route
+    ///
+    pub fn fees_paid(&self) -> Result<Vec<CurrencyAmount<Token>>> {
+        let amounts = self.hop_amounts()?;
+        let mut fees = Vec::with_capacity(amounts.len() - 1);
+        for (amount_in, pool) in amounts.iter().zip(&self.single_swap()?.route.pools) {
+            fees.push(CurrencyAmount::from_raw_amount(
+                amount_in.meta.currency.clone(),
+                (amount_in.as_fraction() * pool.fee.as_fraction()).quotient(),
+            )?);
+        }
+        Ok(fees)
+    }
+
+    /// The execution price of each hop of the trade's route, from the amounts of simulating the
+    /// route hop by hop. Unlike the mid prices of the pools, these include the price impact.
+    ///
+    /// returns: The price of each hop, in the order of the route, or [`Error::MultipleRoutes`] for
+    /// a trade with several routes
+    ///
+    pub fn hop_prices(&self) -> Result<Vec<Price<Token, Token>>> {
+        Ok(self
+            .hop_amounts()?
+            .windows(2)
+            .map(|amounts| {
+                Price::new(
+                    amounts[0].meta.currency.clone(),
+                    amounts[1].meta.currency.clone(),
+                    amounts[0].quotient(),
+                    amounts[1].quotient(),
+                )
+            })
+            .collect())
+    }
+
+    /// The price impact of each hop of the trade's route, i.e. the percent difference between the
+    /// mid price of the pool and the execution price of the hop. The impacts compound, so that
+    /// one minus the product of one minus each impact is the aggregate [`Trade::price_impact`].
+    ///
+    /// returns: The price impact of each hop, in the order of the route. Like the other per hop
+    /// values, it is only defined for single route trades and errors with
+    /// [`Error::MultipleRoutes`] otherwise.
+    ///
+    pub fn price_impact_per_hop(&self) -> Result<Vec<Percent>> {
+        let amounts = self.hop_amounts()?;
+        self.single_swap()?
+            .route
+            .pools
+            .iter()
+            .zip(amounts.windows(2))
+            .map(|(pool, amounts)| {
+                let spot_output_amount = pool
+                    .clone()
+                    .price_of(&amounts[0].meta.currency)
+                    .quote(amounts[0].clone())?;
+                let price_impact = spot_output_amount
+                    .subtract(&amounts[1])?
+                    .divide(&spot_output_amount)?;
+                Ok(Percent::new(
+                    price_impact.numerator(),
+                    price_impact.denominator(),
+                ))
+            })
+            .collect()
+    }
+
+    /// The minimum output of each hop of an exact input trade for the given slippage tolerance,
+    /// which is spread evenly across the hops so that the minimum of the last hop equals
+    /// [`Trade::minimum_amount_out`]. Useful for finding the hop at which a swap reverted.
+    ///
+    /// # Arguments
+    ///
+    /// * `slippage_tolerance`: The tolerance of unfavorable slippage from the execution price of this trade
+    ///
+    /// returns: The minimum output of each hop, in the order of the route, [`Error::NotExactInput`]
+    /// for an exact output trade, or [`Error::MultipleRoutes`] unless the trade has a single route
+    ///
+    pub fn minimum_amounts_per_hop(
+        &self,
+        slippage_tolerance: Percent,
+    ) -> Result<Vec<CurrencyAmount<Token>>> {
+        assert!(
+            slippage_tolerance >= Percent::new(0, 1),
+            "SLIPPAGE_TOLERANCE"
+        );
+        if self.trade_type != TradeType::ExactInput {
+            return Err(Error::NotExactInput.into());
+        }
+        let amounts = self.hop_amounts()?;
+        let num_hops = amounts.len() - 1;
+        amounts
+            .into_iter()
+            .skip(1)
+            .enumerate()
+            .map(|(i, amount_out)| {
+                let hop_tolerance =
+                    slippage_tolerance.as_fraction() * Fraction::new(i + 1, num_hops);
+                let minimum = ((Fraction::new(1, 1) + hop_tolerance).invert()
+                    * Fraction::new(amount_out.quotient(), 1))
+                .quotient();
+                Ok(CurrencyAmount::from_raw_amount(
+                    amount_out.meta.currency,
+                    minimum,
+                )?)
+            })
+            .collect()
+    }
+
+    /// The execution price adjusted for decimals, formatted with exactly `scale` decimal places
+    /// and rounded half up. The division is carried out in integers, so the result is exact up to
+    /// the last decimal place regardless of `scale`.
+    ///
+    /// # Arguments
+    ///
+    /// * `scale`: The number of decimal places to format the price with
+    ///
+    /// returns: String
+    ///
+    pub fn execution_price_decimal(&self, scale: u32) -> String {
+        let input_amount = self.total_input_amount();
+        let output_amount = self.total_output_amount();
+        let ten = BigInt::from(10);
+        let numerator = output_amount.quotient()
+            * ten.pow(input_amount.meta.currency.decimals() as u32 + scale);
+        let denominator =
+            input_amount.quotient() * ten.pow(output_amount.meta.currency.decimals() as u32);
+        let (quotient, remainder) = numerator.div_rem(&denominator);
+        let quotient = if remainder * 2 >= denominator {
+            quotient + 1
+        } else {
+            quotient
+        };
+        format!(
+            "{:.1$}",
+            BigDecimal::new(quotient, scale as i64),
+            scale as usize
+        )
+    }
+
+    /// Returns the percent difference between the route's mid price and the price impact
+    pub fn price_impact(&mut self) -> Result<Percent> {
+        if self._price_impact.is_none() {
+            let output_amount = self.output_amount()?;
+            let mut spot_output_amount =
+                CurrencyAmount::from_raw_amount(output_amount.meta.currency.clone(), 0)?;
+            for Swap {
+                route,
+                input_amount,
+                ..
+            } in &mut self.swaps
+            {
+                let mid_price = route.mid_price()?;
+                spot_output_amount =
+                    spot_output_amount.add(&mid_price.quote(input_amount.clone())?)?;
+            }
+            let price_impact = spot_output_amount
+                .subtract(&output_amount)?
+                .divide(&spot_output_amount)?;
+            self._price_impact = Some(Percent::new(
+                price_impact.numerator(),
+                price_impact.denominator(),
+            ));
+        }
+        Ok(self._price_impact.clone().unwrap())
+    }
+
+    /// Get the minimum amount that must be received from this trade for the given slippage tolerance
+    ///
+    /// # Arguments
+    ///
+    /// * `slippage_tolerance`: The tolerance of unfavorable slippage from the execution price of this trade
+    /// * `amount_out`: The amount to receive
+    ///
+    /// returns: The amount out
+    ///
+    pub fn minimum_amount_out(
+        &mut self,
+        slippage_tolerance: Percent,
+        amount_out: Option<CurrencyAmount<TOutput>>,
+    ) -> Result<CurrencyAmount<TOutput>> {
+        assert!(
+            slippage_tolerance >= Percent::new(0, 1),
+            "SLIPPAGE_TOLERANCE"
+        );
+        let amount_out = match amount_out {
+            Some(amount_out) => amount_out,
+            None => self.output_amount()?,
+        };
+        if self.trade_type == TradeType::ExactOutput {
+            return Ok(amount_out);
+        }
+        let slippage_adjusted_amount_out =
+            ((Fraction::new(1, 1) + slippage_tolerance.as_fraction()).invert()
+                * Fraction::new(amount_out.quotient(), 1))
+            .quotient();
+        Ok(CurrencyAmount::from_raw_amount(
+            amount_out.meta.currency,
+            slippage_adjusted_amount_out,
+        )?)
+    }
+
+    /// Get the maximum amount in that can be spent via this trade for the given slippage tolerance
+    ///
+    /// # Arguments
+    ///
+    /// * `slippage_tolerance`: The tolerance of unfavorable slippage from the execution price of this trade
+    /// * `amount_in`: The amount to spend
+    ///
+    /// returns: The amount in
+    ///
+    pub fn maximum_amount_in(
+        &mut self,
+        slippage_tolerance: Percent,
+        amount_in: Option<CurrencyAmount<TInput>>,
+    ) -> Result<CurrencyAmount<TInput>> {
+        assert!(
+            slippage_tolerance >= Percent::new(0, 1),
+            "SLIPPAGE_TOLERANCE"
+        );
+        let amount_in = match amount_in {
+            Some(amount_in) => amount_in,
+            None => self.input_amount()?,
+        };
+        if self.trade_type == TradeType::ExactInput {
+            return Ok(amount_in);
+        }
+        let slippage_adjusted_amount_in = ((Fraction::new(1, 1)
+            + slippage_tolerance.as_fraction())
+            * Fraction::new(amount_in.quotient(), 1))
+        .quotient();
+        Ok(CurrencyAmount::from_raw_amount(
+            amount_in.meta.currency,
+            slippage_adjusted_amount_in,
+        )?)
+    }
+
+    /// Get the maximum amount in and the minimum amount out for the given slippage tolerance, as
+    /// used in the router calldata
+    ///
+    /// # Arguments
+    ///
+    /// * `slippage_tolerance`: The tolerance of unfavorable slippage from the execution price of this trade
+    ///
+    /// returns: The maximum amount in and the minimum amount out
+    ///
+    pub fn slippage_adjusted_amounts(
+        &mut self,
+        slippage_tolerance: Percent,
+    ) -> Result<(CurrencyAmount<TInput>, CurrencyAmount<TOutput>)> {
+        Ok((
+            self.maximum_amount_in(slippage_tolerance.clone(), None)?,
+            self.minimum_amount_out(slippage_tolerance, None)?,
+        ))
+    }
+
+    /// Get the `value` to send with the router call for this trade, which is the maximum amount in
+    /// for the given slippage tolerance when the input is native and zero otherwise
+    ///
+    /// # Arguments
+    ///
+    /// * `slippage_tolerance`: The tolerance of unfavorable slippage from the execution price of this trade
+    ///
+    /// returns: The native value of the call
+    ///
+    pub fn msg_value(&mut self, slippage_tolerance: Percent) -> Result<U256> {
+        if !self.input_amount()?.meta.currency.is_native() {
+            return Ok(U256::ZERO);
+        }
+        Ok(big_int_to_u256(
+            self.maximum_amount_in(slippage_tolerance, None)?.quotient(),
+        ))
+    }
+
+    /// Return the execution price after accounting for slippage tolerance
+    ///
+    /// # Arguments
+    ///
+    /// * `slippage_tolerance`: the allowed tolerated slippage
+    ///
+    /// returns: The execution price
+    ///
+    pub fn worst_execution_price(
+        &mut self,
+        slippage_tolerance: Percent,
+    ) -> Result<Price<TInput, TOutput>> {
+        let input_amount = self.input_amount()?;
+        let output_amount = self.output_amount()?;
+        Ok(Price::new(
+            input_amount.meta.currency,
+            output_amount.meta.currency,
+            self.maximum_amount_in(slippage_tolerance.clone(), None)?
+                .quotient(),
+            self.minimum_amount_out(slippage_tolerance, None)?
+                .quotient(),
+        ))
+    }
+
+    /// Re-simulates the trade against fresh pool state and checks that the quote has not degraded
+    /// beyond the slippage tolerance. Pools of the trade are replaced by the pool in `pools` with
+    /// the same address, if any.
+    ///
+    /// # Arguments
+    ///
+    /// * `pools`: The fresh pool state to validate against
+    /// * `slippage_tolerance`: The tolerance of unfavorable slippage from the quoted amounts
+    ///
+    /// returns: [`Error::StaleQuote`] if the re-simulated trade falls outside the tolerance
+    ///
+    pub fn validate_against(&self, pools: &[Pool], slippage_tolerance: Percent) -> Result<()> {
+        let fresh_route = |route: &Route<TInput, TOutput>| {
+            let fresh_pools = route
+                .pools
+                .iter()
+                .map(|pool| {
+                    let address = pool.address();
+                    pools
+                        .iter()
+                        .find(|fresh| fresh.address() == address)
+                        .unwrap_or(pool)
+                        .clone()
+                })
+                .collect();
+            Route::new(fresh_pools, route.input.clone(), route.output.clone())
+        };
+        let is_stale = match self.trade_type {
+            TradeType::ExactInput => {
+                let routes = self
+                    .swaps
+                    .iter()
+                    .map(|swap| Ok((swap.input_amount.clone(), fresh_route(&swap.route)?)))
+                    .collect::<Result<Vec<_>>>()?;
+                let mut fresh = Self::from_routes(routes, self.trade_type)?;
+                fresh.output_amount()?.quotient()
+                    < self
+                        .clone()
+                        .minimum_amount_out(slippage_tolerance, None)?
+                        .quotient()
+            }
+            TradeType::ExactOutput => {
+                let routes = self
+                    .swaps
+                    .iter()
+                    .map(|swap| Ok((swap.output_amount.clone(), fresh_route(&swap.route)?)))
+                    .collect::<Result<Vec<_>>>()?;
+                let mut fresh = Self::from_routes(routes, self.trade_type)?;
+                fresh.input_amount()?.quotient()
+                    > self
+                        .clone()
+                        .maximum_amount_in(slippage_tolerance, None)?
+                        .quotient()
+            }
+        };
+        if is_stale {
+            return Err(Error::StaleQuote.into());
+        }
+        Ok(())
+    }
+}
+
+impl<TInput, TOutput> Trade<TInput, TOutput>
+where
+    TInput: CurrencyTrait + PartialEq,
+    TOutput: CurrencyTrait + PartialEq,
+{
+    /// Merges two partial fills of the same routes into a single trade by summing the input and
+    /// output amounts of each swap
+    ///
+    /// # Arguments
+    ///
+    /// * `other`: The trade to merge into this one, which must have identical routes and trade type
+    ///
+    /// returns: Trade
+    ///
+    pub fn merge(self, other: Self) -> Result<Self> {
+        if self.trade_type != other.trade_type
+            || self.swaps.len() != other.swaps.len()
+            || self
+                .swaps
+                .iter()
+                .zip(&other.swaps)
+                .any(|(a, b)| a.route != b.route)
+        {
+            return Err(Error::IncompatibleTrades.into());
+        }
+        let mut swaps = Vec::with_capacity(self.swaps.len());
+        for (a, b) in self.swaps.into_iter().zip(other.swaps) {
+            swaps.push(Swap {
+                route: a.route,
+                input_amount: a.input_amount.add(&b.input_amount)?,
+                output_amount: a.output_amount.add(&b.output_amount)?,
+            });
+        }
+        Self::new(swaps, self.trade_type)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use once_cell::sync::Lazy;
+    use std::sync::Arc;
+    use uniswap_sdk_core::{token, utils::sqrt::sqrt};
+
+    static ETHER: Lazy<Ether> = Lazy::new(|| Ether::on_chain(1));
+    static TOKEN0: Lazy<Token> = Lazy::new(|| {
+        token!(
+            1,
+            "0x0000000000000000000000000000000000000001",
+            18,
+            "t0",
+            "token0"
+        )
+    });
+    static TOKEN1: Lazy<Token> = Lazy::new(|| {
+        token!(
+            1,
+            "0x0000000000000000000000000000000000000002",
+            18,
+            "t1",
+            "token1"
+        )
+    });
+    static TOKEN2: Lazy<Token> = Lazy::new(|| {
+        token!(
+            1,
+            "0x0000000000000000000000000000000000000003",
+            18,
+            "t2",
+            "token2"
+        )
+    });
+    static WETH: Lazy<Token> = Lazy::new(|| WETH9::default().get(1).unwrap().clone());
+
+    fn v2_style_pool(
+        reserve0: CurrencyAmount<Token>,
+        reserve1: CurrencyAmount<Token>,
+        fee_amount: FeeAmount,
+    ) -> Pool {
+        let sqrt_ratio_x96 = encode_sqrt_ratio_x96(reserve1.quotient(), reserve0.quotient());
+        let liquidity = sqrt(&(reserve0.quotient() * reserve1.quotient()))
+            .unwrap()
+            .to_u128()
+            .unwrap();
+        Pool::new(
+            reserve0.meta.currency,
+            reserve1.meta.currency,
+            fee_amount,
+            sqrt_ratio_x96,
+            liquidity,
+            Some(Arc::new(TickListDataProvider::new(
+                vec![
+                    Tick::new(
+                        nearest_usable_tick(MIN_TICK, fee_amount.tick_spacing()),
+                        liquidity,
+                        liquidity as i128,
+                    ),
+                    Tick::new(
+                        nearest_usable_tick(MAX_TICK, fee_amount.tick_spacing()),
+                        liquidity,
+                        -(liquidity as i128),
+                    ),
+                ],
+                fee_amount.tick_spacing(),
+            ))),
+        )
+        .unwrap()
+    }
+
+    fn pool_0_1() -> Pool {
+        v2_style_pool(
+            CurrencyAmount::from_raw_amount(TOKEN0.clone(), 100000).unwrap(),
+            CurrencyAmount::from_raw_amount(TOKEN1.clone(), 100000).unwrap(),
+            FeeAmount::MEDIUM,
+        )
+    }
+
+    fn pool_0_2() -> Pool {
+        v2_style_pool(
+            CurrencyAmount::from_raw_amount(TOKEN0.clone(), 100000).unwrap(),
+            CurrencyAmount::from_raw_amount(TOKEN2.clone(), 110000).unwrap(),
+            FeeAmount::MEDIUM,
+        )
+    }
+
+    fn pool_1_2() -> Pool {
+        v2_style_pool(
+            CurrencyAmount::from_raw_amount(TOKEN1.clone(), 120000).unwrap(),
+            CurrencyAmount::from_raw_amount(TOKEN2.clone(), 100000).unwrap(),
+            FeeAmount::MEDIUM,
+        )
+    }
+
+    fn pool_weth_0() -> Pool {
+        v2_style_pool(
+            CurrencyAmount::from_raw_amount(WETH.clone(), 100000).unwrap(),
+            CurrencyAmount::from_raw_amount(TOKEN0.clone(), 100000).unwrap(),
+            FeeAmount::MEDIUM,
+        )
+    }
+
+    #[test]
+    fn exact_in_can_be_constructed_with_ether_as_input() -> Result<()> {
+        let mut trade = Trade::exact_in(
+            Route::new(vec![pool_weth_0()], ETHER.clone(), TOKEN0.clone())?,
+            CurrencyAmount::from_raw_amount(ETHER.clone(), 10000)?,
+        )?;
+        assert!(trade.input_amount()?.meta.currency.equals(&ETHER.clone()));
+        assert!(trade.output_amount()?.meta.currency.equals(&TOKEN0.clone()));
+        Ok(())
+    }
+
+    #[test]
+    fn ether_input_amount_is_not_wrapped() -> Result<()> {
+        let route = Route::new(vec![pool_weth_0()], ETHER.clone(), TOKEN0.clone())?;
+        let mut exact_in = Trade::exact_in(
+            route.clone(),
+            CurrencyAmount::from_raw_amount(ETHER.clone(), 10000)?,
+        )?;
+        let mut exact_out =
+            Trade::exact_out(route, CurrencyAmount::from_raw_amount(TOKEN0.clone(), 100)?)?;
+        for input_amount in [exact_in.input_amount()?, exact_out.input_amount()?] {
+            assert!(input_amount.meta.currency.is_native());
+            assert!(!input_amount.meta.currency.equals(&WETH.clone()));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn exact_out_can_be_constructed_with_ether_as_output() -> Result<()> {
+        let mut trade = Trade::exact_out(
+            Route::new(vec![pool_weth_0()], TOKEN0.clone(), ETHER.clone())?,
+            CurrencyAmount::from_raw_amount(ETHER.clone(), 10000)?,
+        )?;
+        assert!(trade.input_amount()?.meta.currency.equals(&TOKEN0.clone()));
+        assert!(trade.output_amount()?.meta.currency.equals(&ETHER.clone()));
+        Ok(())
+    }
+
+    #[test]
+    fn exact_in_multi_hop() -> Result<()> {
+        let mut trade = Trade::exact_in(
+            Route::new(vec![pool_0_1(), pool_1_2()], TOKEN0.clone(), TOKEN2.clone())?,
+            CurrencyAmount::from_raw_amount(TOKEN0.clone(), 10000)?,
+        )?;
+        assert_eq!(trade.input_amount()?.quotient(), 10000.into());
+        assert_eq!(trade.output_amount()?.quotient(), 7004.into());
+        Ok(())
+    }
+
+    #[test]
+    fn minimum_amounts_per_hop_are_below_simulated_outputs() -> Result<()> {
+        let mut trade = Trade::exact_in(
+            Route::new(vec![pool_0_1(), pool_1_2()], TOKEN0.clone(), TOKEN2.clone())?,
+            CurrencyAmount::from_raw_amount(TOKEN0.clone(), 10000)?,
+        )?;
+        let slippage_tolerance = Percent::new(5, 100);
+        let minimums = trade.minimum_amounts_per_hop(slippage_tolerance.clone())?;
+        let outputs = trade.hop_amounts()?;
+        assert_eq!(minimums.len(), 2);
+        for (minimum, output) in minimums.iter().zip(&outputs[1..]) {
+            assert_eq!(minimum.meta.currency, output.meta.currency);
+            assert!(minimum.quotient() < output.quotient());
+        }
+        assert_eq!(
+            minimums[1].quotient(),
+            trade
+                .minimum_amount_out(slippage_tolerance, None)?
+                .quotient()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn minimum_amounts_per_hop_error_for_exact_output() -> Result<()> {
+        let trade = Trade::exact_out(
+            Route::new(vec![pool_0_1(), pool_1_2()], TOKEN0.clone(), TOKEN2.clone())?,
+            CurrencyAmount::from_raw_amount(TOKEN2.clone(), 100)?,
+        )?;
+        let err = trade
+            .minimum_amounts_per_hop(Percent::new(5, 100))
+            .unwrap_err();
+        assert!(matches!(err.downcast_ref(), Some(Error::NotExactInput)));
+        Ok(())
+    }
+
+    #[test]
+    fn slippage_from_bps_flows_through_the_minimum_amount_out() -> Result<()> {
+        assert_eq!(slippage_from_bps(50), Percent::new(5, 1000));
+        let mut trade = Trade::exact_in(
+            Route::new(vec![pool_0_1()], TOKEN0.clone(), TOKEN1.clone())?,
+            CurrencyAmount::from_raw_amount(TOKEN0.clone(), 100)?,
+        )?;
+        assert_eq!(
+            trade
+                .minimum_amount_out(slippage_from_bps(500), None)?
+                .quotient(),
+            trade
+                .minimum_amount_out(Percent::new(5, 100), None)?
+                .quotient()
+        );
+        assert_eq!(
+            trade
+                .minimum_amount_out(slippage_from_bps(0), None)?
+                .quotient(),
+            trade.output_amount()?.quotient()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn zero_amount_trades_error() -> Result<()> {
+        let route = Route::new(vec![pool_0_1()], TOKEN0.clone(), TOKEN1.clone())?;
+        let err = Trade::exact_in(
+            route.clone(),
+            CurrencyAmount::from_raw_amount(TOKEN0.clone(), 0)?,
+        )
+        .unwrap_err();
+        assert!(matches!(err.downcast_ref(), Some(Error::ZeroAmount)));
+        let err = Trade::exact_out(route, CurrencyAmount::from_raw_amount(TOKEN1.clone(), 0)?)
+            .unwrap_err();
+        assert!(matches!(err.downcast_ref(), Some(Error::ZeroAmount)));
+        Ok(())
+    }
+
+    #[test]
+    fn hop_prices_chain_to_the_execution_price() -> Result<()> {
+        let route = Route::new(vec![pool_0_1(), pool_1_2()], TOKEN0.clone(), TOKEN2.clone())?;
+        for mut trade in [
+            Trade::exact_in(
+                route.clone(),
+                CurrencyAmount::from_raw_amount(TOKEN0.clone(), 10000)?,
+            )?,
+            Trade::exact_out(
+                route.clone(),
+                CurrencyAmount::from_raw_amount(TOKEN2.clone(), 7004)?,
+            )?,
+        ] {
+            let prices = trade.hop_prices()?;
+            assert_eq!(prices.len(), 2);
+            assert_eq!(prices[0].meta.base_currency, TOKEN0.clone());
+            assert_eq!(prices[0].meta.quote_currency, TOKEN1.clone());
+            assert_eq!(prices[1].meta.quote_currency, TOKEN2.clone());
+            let chained = prices[0].multiply(&prices[1])?;
+            assert_eq!(chained, trade.execution_price()?);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn price_impact_per_hop_compounds_to_the_aggregate() -> Result<()> {
+        let mut trade = Trade::exact_in(
+            Route::new(vec![pool_0_1(), pool_1_2()], TOKEN0.clone(), TOKEN2.clone())?,
+            CurrencyAmount::from_raw_amount(TOKEN0.clone(), 10000)?,
+        )?;
+        let impacts = trade.price_impact_per_hop()?;
+        assert_eq!(impacts.len(), 2);
+        let zero = Fraction::new(0, 1);
+        let one = Fraction::new(1, 1);
+        assert!(impacts.iter().all(|impact| impact.as_fraction() > zero));
+        let aggregate = trade.price_impact()?.as_fraction();
+        let compounded = one.clone()
+            - (one.clone() - impacts[0].as_fraction()) * (one - impacts[1].as_fraction());
+        assert_eq!(compounded, aggregate);
+        // the sum overestimates the aggregate by the product of the impacts
+        let sum = impacts[0].as_fraction() + impacts[1].as_fraction();
+        assert!(sum >= aggregate);
+        assert_eq!(
+            sum - aggregate,
+            impacts[0].as_fraction() * impacts[1].as_fraction()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn fees_paid_per_hop() -> Result<()> {
+        let trade = Trade::exact_in(
+            Route::new(vec![pool_0_1(), pool_1_2()], TOKEN0.clone(), TOKEN2.clone())?,
+            CurrencyAmount::from_raw_amount(TOKEN0.clone(), 10000)?,
+        )?;
+        let (intermediate, _) = pool_0_1().get_output_amount(
+            CurrencyAmount::from_raw_amount(TOKEN0.clone(), 10000)?,
+            None,
+        )?;
+        let fees = trade.fees_paid()?;
+        assert_eq!(fees.len(), 2);
+        assert_eq!(fees[0].meta.currency, TOKEN0.clone());
+        assert_eq!(fees[0].quotient(), BigInt::from(30));
+        assert_eq!(fees[1].meta.currency, TOKEN1.clone());
+        assert_eq!(
+            fees[1].quotient(),
+            intermediate.quotient() * 3000 / 1_000_000
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn per_hop_values_error_for_multiple_routes() -> Result<()> {
+        let trade = Trade::from_routes(
+            vec![
+                (
+                    CurrencyAmount::from_raw_amount(TOKEN0.clone(), 5000)?,
+                    Route::new(vec![pool_0_2()], TOKEN0.clone(), TOKEN2.clone())?,
+                ),
+                (
+                    CurrencyAmount::from_raw_amount(TOKEN0.clone(), 5000)?,
+                    Route::new(vec![pool_0_1(), pool_1_2()], TOKEN0.clone(), TOKEN2.clone())?,
+                ),
+            ],
+            TradeType::ExactInput,
+        )?;
+        let is_multiple_routes =
+            |err: anyhow::Error| matches!(err.downcast_ref(), Some(Error::MultipleRoutes));
+        assert!(is_multiple_routes(trade.fees_paid().unwrap_err()));
+        assert!(is_multiple_routes(trade.hop_prices().unwrap_err()));
+        assert!(is_multiple_routes(
+            trade.price_impact_per_hop().unwrap_err()
+        ));
+        assert!(is_multiple_routes(
+            trade
+                .minimum_amounts_per_hop(Percent::new(1, 100))
+                .unwrap_err()
+        ));
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic(expected = "POOLS_DUPLICATED")]
+    fn throws_if_pools_are_reused_between_routes() {
+        let route = Route::new(vec![pool_0_1()], TOKEN0.clone(), TOKEN1.clone()).unwrap();
+        let input_amount = CurrencyAmount::from_raw_amount(TOKEN0.clone(), 4500).unwrap();
+        let output_amount = CurrencyAmount::from_raw_amount(TOKEN1.clone(), 4500).unwrap();
+        Trade::create_unchecked_trade_with_multiple_routes(
+            vec![
+                Swap {
+                    route: route.clone(),
+                    input_amount: input_amount.clone(),
+                    output_amount: output_amount.clone(),
+                },
+                Swap {
+                    route,
+                    input_amount,
+                    output_amount,
+                },
+            ],
+            TradeType::ExactInput,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn minimum_amount_out_and_maximum_amount_in() -> Result<()> {
+        let route = Route::new(vec![pool_0_1()], TOKEN0.clone(), TOKEN1.clone())?;
+        let mut trade = Trade::create_unchecked_trade(
+            route.clone(),
+            CurrencyAmount::from_raw_amount(TOKEN0.clone(), 100)?,
+            CurrencyAmount::from_raw_amount(TOKEN1.clone(), 69)?,
+            TradeType::ExactInput,
+        )?;
+        assert_eq!(
+            trade
+                .minimum_amount_out(Percent::new(5, 100), None)?
+                .quotient(),
+            65.into()
+        );
+        assert_eq!(
+            trade
+                .maximum_amount_in(Percent::new(5, 100), None)?
+                .quotient(),
+            100.into()
+        );
+        let mut trade = Trade::create_unchecked_trade(
+            route,
+            CurrencyAmount::from_raw_amount(TOKEN0.clone(), 156)?,
+            CurrencyAmount::from_raw_amount(TOKEN1.clone(), 100)?,
+            TradeType::ExactOutput,
+        )?;
+        assert_eq!(
+            trade
+                .minimum_amount_out(Percent::new(5, 100), None)?
+                .quotient(),
+            100.into()
+        );
+        assert_eq!(
+            trade
+                .maximum_amount_in(Percent::new(5, 100), None)?
+                .quotient(),
+            163.into()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn slippage_adjusted_amounts_match_individual_bounds() -> Result<()> {
+        let route = Route::new(vec![pool_0_1()], TOKEN0.clone(), TOKEN1.clone())?;
+        for (input, output, trade_type) in [
+            (100, 69, TradeType::ExactInput),
+            (156, 100, TradeType::ExactOutput),
+        ] {
+            let mut trade = Trade::create_unchecked_trade(
+                route.clone(),
+                CurrencyAmount::from_raw_amount(TOKEN0.clone(), input)?,
+                CurrencyAmount::from_raw_amount(TOKEN1.clone(), output)?,
+                trade_type,
+            )?;
+            let slippage_tolerance = Percent::new(5, 100);
+            let (maximum_amount_in, minimum_amount_out) =
+                trade.slippage_adjusted_amounts(slippage_tolerance.clone())?;
+            assert_eq!(
+                maximum_amount_in,
+                trade.maximum_amount_in(slippage_tolerance.clone(), None)?
+            );
+            assert_eq!(
+                minimum_amount_out,
+                trade.minimum_amount_out(slippage_tolerance, None)?
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn msg_value() -> Result<()> {
+        let slippage_tolerance = Percent::new(5, 100);
+        let mut trade = Trade::exact_in(
+            Route::new(vec![pool_weth_0()], ETHER.clone(), TOKEN0.clone())?,
+            CurrencyAmount::from_raw_amount(ETHER.clone(), 10000)?,
+        )?;
+        assert_eq!(
+            trade.msg_value(slippage_tolerance.clone())?,
+            U256::from(10000)
+        );
+
+        let mut trade = Trade::exact_out(
+            Route::new(vec![pool_weth_0()], ETHER.clone(), TOKEN0.clone())?,
+            CurrencyAmount::from_raw_amount(TOKEN0.clone(), 10000)?,
+        )?;
+        let maximum_amount_in = trade.maximum_amount_in(slippage_tolerance.clone(), None)?;
+        assert!(maximum_amount_in.quotient() > trade.input_amount()?.quotient());
+        assert_eq!(
+            trade.msg_value(slippage_tolerance.clone())?,
+            big_int_to_u256(maximum_amount_in.quotient())
+        );
+
+        let mut trade = Trade::exact_in(
+            Route::new(vec![pool_weth_0()], TOKEN0.clone(), ETHER.clone())?,
+            CurrencyAmount::from_raw_amount(TOKEN0.clone(), 10000)?,
+        )?;
+        assert_eq!(trade.msg_value(slippage_tolerance)?, U256::ZERO);
+        Ok(())
+    }
+
+    #[test]
+    fn from_quoter_result_uses_the_quoted_amount() -> Result<()> {
+        let route = Route::new(vec![pool_0_1()], TOKEN0.clone(), TOKEN1.clone())?;
+        let mut trade = Trade::from_quoter_result(
+            route.clone(),
+            CurrencyAmount::from_raw_amount(TOKEN0.clone(), 100)?,
+            TradeType::ExactInput,
+            U256::from(50),
+        )?;
+        assert_eq!(trade.input_amount()?.quotient(), BigInt::from(100));
+        assert_eq!(trade.output_amount()?.quotient(), BigInt::from(50));
+        assert_eq!(
+            trade.execution_price()?,
+            Price::new(TOKEN0.clone(), TOKEN1.clone(), 100, 50)
+        );
+
+        let mut trade = Trade::from_quoter_result(
+            route,
+            CurrencyAmount::from_raw_amount(TOKEN1.clone(), 100)?,
+            TradeType::ExactOutput,
+            U256::from(250),
+        )?;
+        assert_eq!(trade.input_amount()?.quotient(), BigInt::from(250));
+        assert_eq!(trade.output_amount()?.quotient(), BigInt::from(100));
+        assert_eq!(
+            trade.execution_price()?,
+            Price::new(TOKEN0.clone(), TOKEN1.clone(), 250, 100)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn structurally_identical_trades_dedupe() -> Result<()> {
+        let trade = || {
+            Trade::exact_in(
+                Route::new(vec![pool_0_1(), pool_1_2()], TOKEN0.clone(), TOKEN2.clone())?,
+                CurrencyAmount::from_raw_amount(TOKEN0.clone(), 10000)?,
+            )
+        };
+        let other = Trade::exact_in(
+            Route::new(vec![pool_0_2()], TOKEN0.clone(), TOKEN2.clone())?,
+            CurrencyAmount::from_raw_amount(TOKEN0.clone(), 10000)?,
+        )?;
+        let trades = HashSet::from([trade()?, trade()?, other]);
+        assert_eq!(trades.len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn merge_sums_amounts_of_same_route_trades() -> Result<()> {
+        let route = Route::new(vec![pool_0_1()], TOKEN0.clone(), TOKEN1.clone())?;
+        let mut first = Trade::exact_in(
+            route.clone(),
+            CurrencyAmount::from_raw_amount(TOKEN0.clone(), 1000)?,
+        )?;
+        let mut second = Trade::exact_in(
+            route,
+            CurrencyAmount::from_raw_amount(TOKEN0.clone(), 2000)?,
+        )?;
+        let expected_output = first.output_amount()?.add(&second.output_amount()?)?;
+        let mut merged = first.merge(second)?;
+        assert_eq!(merged.swaps.len(), 1);
+        assert_eq!(merged.input_amount()?.quotient(), 3000.into());
+        assert_eq!(merged.output_amount()?, expected_output);
+        Ok(())
+    }
+
+    #[test]
+    fn merge_rejects_different_trade_types() -> Result<()> {
+        let route = Route::new(vec![pool_0_1()], TOKEN0.clone(), TOKEN1.clone())?;
+        let exact_in = Trade::exact_in(
+            route.clone(),
+            CurrencyAmount::from_raw_amount(TOKEN0.clone(), 1000)?,
+        )?;
+        let exact_out = Trade::exact_out(
+            route,
+            CurrencyAmount::from_raw_amount(TOKEN1.clone(), 1000)?,
+        )?;
+        assert!(exact_in.merge(exact_out).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn validate_against_fails_after_the_price_moved() -> Result<()> {
+        let trade = Trade::exact_in(
+            Route::new(vec![pool_0_1()], TOKEN0.clone(), TOKEN1.clone())?,
+            CurrencyAmount::from_raw_amount(TOKEN0.clone(), 1000)?,
+        )?;
+        trade.validate_against(&[pool_0_1()], Percent::new(1, 100))?;
+
+        let moved = v2_style_pool(
+            CurrencyAmount::from_raw_amount(TOKEN0.clone(), 110000).unwrap(),
+            CurrencyAmount::from_raw_amount(TOKEN1.clone(), 90000).unwrap(),
+            FeeAmount::MEDIUM,
+        );
+        let err = trade
+            .validate_against(&[moved], Percent::new(1, 100))
+            .unwrap_err();
+        assert!(matches!(err.downcast_ref(), Some(Error::StaleQuote)));
+        Ok(())
+    }
+
+    #[test]
+    fn best_split_two_beats_either_single_route() -> Result<()> {
+        let route_a = Route::new(vec![pool_0_1()], TOKEN0.clone(), TOKEN1.clone())?;
+        let route_b = Route::new(
+            vec![v2_style_pool(
+                CurrencyAmount::from_raw_amount(TOKEN0.clone(), 100000).unwrap(),
+                CurrencyAmount::from_raw_amount(TOKEN1.clone(), 100000).unwrap(),
+                FeeAmount::LOW,
+            )],
+            TOKEN0.clone(),
+            TOKEN1.clone(),
+        )?;
+        let amount_in = CurrencyAmount::from_raw_amount(TOKEN0.clone(), 10000)?;
+        let single_a = Trade::exact_in(route_a.clone(), amount_in.clone())?
+            .output_amount()?
+            .quotient();
+        let single_b = Trade::exact_in(route_b.clone(), amount_in.clone())?
+            .output_amount()?
+            .quotient();
+
+        let mut split = Trade::best_split_two(route_a, route_b, amount_in, 10)?;
+        assert_eq!(split.swaps.len(), 2);
+        assert_eq!(split.input_amount()?.quotient(), 10000.into());
+        let output = split.output_amount()?.quotient();
+        assert!(output > single_a);
+        assert!(output > single_b);
+        Ok(())
+    }
+
+    #[test]
+    fn best_trade_exact_in_provides_best_route() -> Result<()> {
+        let mut best_trades = vec![];
+        let result = Trade::best_trade_exact_in(
+            vec![pool_0_1(), pool_0_2(), pool_1_2()],
+            &CurrencyAmount::from_raw_amount(TOKEN0.clone(), 10000)?,
+            &TOKEN2.clone(),
+            BestTradeOptions::default(),
+            vec![],
+            None,
+            &mut best_trades,
+        )?;
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].swaps[0].route.pools, vec![pool_0_2()]);
+        assert_eq!(result[0].input_amount()?.quotient(), 10000.into());
+        assert_eq!(result[0].output_amount()?.quotient(), 9971.into());
+        assert_eq!(result[1].swaps[0].route.pools, vec![pool_0_1(), pool_1_2()]);
+        assert_eq!(result[1].input_amount()?.quotient(), 10000.into());
+        assert_eq!(result[1].output_amount()?.quotient(), 7004.into());
+        Ok(())
+    }
+
+    #[test]
+    fn best_trade_exact_in_prefers_fewer_hops_on_equal_output() -> Result<()> {
+        // the direct pool is priced so that it yields the same output as the two hop route
+        let pool_0_2 = v2_style_pool(
+            CurrencyAmount::from_raw_amount(TOKEN0.clone(), 100000)?,
+            CurrencyAmount::from_raw_amount(TOKEN2.clone(), 77260)?,
+            FeeAmount::MEDIUM,
+        );
+        let mut best_trades = vec![];
+        let result = Trade::best_trade_exact_in(
+            vec![pool_0_1(), pool_1_2(), pool_0_2.clone()],
+            &CurrencyAmount::from_raw_amount(TOKEN0.clone(), 10000)?,
+            &TOKEN2.clone(),
+            BestTradeOptions::default(),
+            vec![],
+            None,
+            &mut best_trades,
+        )?;
+        assert_eq!(result.len(), 2);
+        assert_eq!(
+            result[0].output_amount()?.quotient(),
+            result[1].output_amount()?.quotient()
+        );
+        assert_eq!(result[0].swaps[0].route.pools, vec![pool_0_2]);
+        assert_eq!(result[1].swaps[0].route.pools, vec![pool_0_1(), pool_1_2()]);
+        Ok(())
+    }
+
+    #[test]
+    fn best_trades_skip_pools_that_cannot_fill() -> Result<()> {
+        let no_ticks = Pool::new(
+            TOKEN0.clone(),
+            TOKEN2.clone(),
+            FeeAmount::HIGH,
+            encode_sqrt_ratio_x96(1, 1),
+            0,
+            None,
+        )?;
+        let shallow = v2_style_pool(
+            CurrencyAmount::from_raw_amount(TOKEN0.clone(), 100)?,
+            CurrencyAmount::from_raw_amount(TOKEN2.clone(), 100)?,
+            FeeAmount::LOW,
+        );
+        let pools = vec![no_ticks, shallow, pool_0_1(), pool_0_2(), pool_1_2()];
+
+        let mut best_trades = vec![];
+        let result = Trade::best_trade_exact_in(
+            pools.clone(),
+            &CurrencyAmount::from_raw_amount(TOKEN0.clone(), 10000)?,
+            &TOKEN2.clone(),
+            BestTradeOptions::default(),
+            vec![],
+            None,
+            &mut best_trades,
+        )?;
+        assert_eq!(result[0].swaps[0].route.pools, vec![pool_0_2()]);
+        assert!(result
+            .iter()
+            .all(|trade| trade.swaps[0].route.pools[0].fee != FeeAmount::HIGH));
+
+        let mut best_trades = vec![];
+        let result = Trade::best_trade_exact_out(
+            pools,
+            &TOKEN0.clone(),
+            &CurrencyAmount::from_raw_amount(TOKEN2.clone(), 10000)?,
+            BestTradeOptions::default(),
+            vec![],
+            None,
+            &mut best_trades,
+        )?;
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].swaps[0].route.pools, vec![pool_0_2()]);
+        assert_eq!(result[1].swaps[0].route.pools, vec![pool_0_1(), pool_1_2()]);
+        Ok(())
+    }
+
+    #[test]
+    fn best_trade_exact_in_dedupes_routes() -> Result<()> {
+        // the same pool twice yields two trades over the same token path and fee tiers
+        let mut best_trades = vec![];
+        let result = Trade::best_trade_exact_in(
+            vec![pool_0_1(), pool_0_1()],
+            &CurrencyAmount::from_raw_amount(TOKEN0.clone(), 10000)?,
+            &TOKEN1.clone(),
+            BestTradeOptions::default(),
+            vec![],
+            None,
+            &mut best_trades,
+        )?;
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].swaps[0].route.pools, vec![pool_0_1()]);
+
+        let mut best_trades = vec![];
+        let result = Trade::best_trade_exact_out(
+            vec![pool_0_1(), pool_0_1()],
+            &TOKEN0.clone(),
+            &CurrencyAmount::from_raw_amount(TOKEN1.clone(), 10000)?,
+            BestTradeOptions::default(),
+            vec![],
+            None,
+            &mut best_trades,
+        )?;
+        assert_eq!(result.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn best_trade_exact_in_keeps_the_best_of_duplicate_routes() -> Result<()> {
+        // a pool with the same tokens and fee but more liquidity gives a better output
+        let deep_pool_0_1 = v2_style_pool(
+            CurrencyAmount::from_raw_amount(TOKEN0.clone(), 1000000)?,
+            CurrencyAmount::from_raw_amount(TOKEN1.clone(), 1000000)?,
+            FeeAmount::MEDIUM,
+        );
+        let mut best_trades = vec![];
+        let result = Trade::best_trade_exact_in(
+            vec![pool_0_1(), deep_pool_0_1.clone()],
+            &CurrencyAmount::from_raw_amount(TOKEN0.clone(), 10000)?,
+            &TOKEN1.clone(),
+            BestTradeOptions::default(),
+            vec![],
+            None,
+            &mut best_trades,
+        )?;
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].swaps[0].route.pools, vec![deep_pool_0_1]);
+        Ok(())
+    }
+
+    #[test]
+    fn best_trade_exact_out_provides_best_route() -> Result<()> {
+        let mut best_trades = vec![];
+        let result = Trade::best_trade_exact_out(
+            vec![pool_0_1(), pool_0_2(), pool_1_2()],
+            &TOKEN0.clone(),
+            &CurrencyAmount::from_raw_amount(TOKEN2.clone(), 10000)?,
+            BestTradeOptions::default(),
+            vec![],
+            None,
+            &mut best_trades,
+        )?;
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].swaps[0].route.pools, vec![pool_0_2()]);
+        assert_eq!(result[0].input_amount()?.quotient(), 10032.into());
+        assert_eq!(result[0].output_amount()?.quotient(), 10000.into());
+        assert_eq!(result[1].swaps[0].route.pools, vec![pool_0_1(), pool_1_2()]);
+        assert_eq!(result[1].input_amount()?.quotient(), 15488.into());
+        assert_eq!(result[1].output_amount()?.quotient(), 10000.into());
+        Ok(())
+    }
+
+    #[test]
+    fn best_trade_rejects_max_hops_above_the_limit() -> Result<()> {
+        let options = BestTradeOptions {
+            max_num_results: None,
+            max_hops: Some(10),
+        };
+        let err = Trade::best_trade_exact_in(
+            vec![pool_0_1(), pool_0_2(), pool_1_2()],
+            &CurrencyAmount::from_raw_amount(TOKEN0.clone(), 10000)?,
+            &TOKEN2.clone(),
+            options,
+            vec![],
+            None,
+            &mut vec![],
+        )
+        .unwrap_err();
+        assert!(matches!(err.downcast_ref(), Some(Error::MaxHopsExceeded)));
+        let err = Trade::best_trade_exact_out(
+            vec![pool_0_1(), pool_0_2(), pool_1_2()],
+            &TOKEN0.clone(),
+            &CurrencyAmount::from_raw_amount(TOKEN2.clone(), 10000)?,
+            options,
+            vec![],
+            None,
+            &mut vec![],
+        )
+        .unwrap_err();
+        assert!(matches!(err.downcast_ref(), Some(Error::MaxHopsExceeded)));
+        Ok(())
+    }
+
+    #[test]
+    fn execution_price_decimal_is_exact_at_high_scale() -> Result<()> {
+        let route = Route::new(vec![pool_0_1()], TOKEN0.clone(), TOKEN1.clone())?;
+        let trade = Trade::create_unchecked_trade(
+            route.clone(),
+            CurrencyAmount::from_raw_amount(TOKEN0.clone(), 3)?,
+            CurrencyAmount::from_raw_amount(TOKEN1.clone(), 1)?,
+            TradeType::ExactInput,
+        )?;
+        assert_eq!(
+            trade.execution_price_decimal(50),
+            format!("0.{}", "3".repeat(50))
+        );
+        let trade = Trade::create_unchecked_trade(
+            route,
+            CurrencyAmount::from_raw_amount(TOKEN0.clone(), 3)?,
+            CurrencyAmount::from_raw_amount(TOKEN1.clone(), 2)?,
+            TradeType::ExactInput,
+        )?;
+        assert_eq!(
+            trade.execution_price_decimal(50),
+            format!("0.{}7", "6".repeat(49))
+        );
+        assert_eq!(trade.execution_price_decimal(0), "1");
+        Ok(())
+    }
+
+    #[test]
+    fn trades_with_zero_input_are_rejected() -> Result<()> {
+        let route = Route::new(vec![pool_0_1()], TOKEN0.clone(), TOKEN1.clone())?;
+        let err = Trade::create_unchecked_trade(
+            route.clone(),
+            CurrencyAmount::from_raw_amount(TOKEN0.clone(), 0)?,
+            CurrencyAmount::from_raw_amount(TOKEN1.clone(), 1)?,
+            TradeType::ExactInput,
+        )
+        .unwrap_err();
+        assert!(matches!(err.downcast_ref(), Some(Error::ZeroAmount)));
+        let err = Trade::from_quoter_result(
+            route,
+            CurrencyAmount::from_raw_amount(TOKEN1.clone(), 1)?,
+            TradeType::ExactOutput,
+            U256::ZERO,
+        )
+        .unwrap_err();
+        assert!(matches!(err.downcast_ref(), Some(Error::ZeroAmount)));
+        Ok(())
+    }
+}
@@ -1,16 +1,104 @@
 use thiserror::Error;
+use uniswap_v3_math::error::UniswapV3MathError;
 
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("Should not be zero")]
     IsZero,
 
+    #[error("amount must not be zero")]
+    ZeroAmount,
+
     #[error("diiferent chain id")]
     ChainIdIsDifferent,
 
     #[error("last pool does not involve specific token in the output")]
     InvolvesToken,
 
-    #[error("Token not present in current pool")]
-    TokenNotInPool,
+    /// The token path breaks at the pool with the given index
+    #[error("Token not present in pool at index {0}")]
+    TokenNotInPool(usize),
+
+    /// The pool with the given index does not contain the output token of the previous pool
+    #[error("route is disconnected at pool index {0}")]
+    DisconnectedRoute(usize),
+
+    /// The pool with the given index is not for the same token pair as the first pool
+    #[error("pool at index {0} is for a different token pair")]
+    PairMismatch(usize),
+
+    #[error("no pool of the token pair")]
+    NoPoolForPair,
+
+    #[error("tokens must be different")]
+    IdenticalTokens,
+
+    #[error("trades must share the same routes and trade type")]
+    IncompatibleTrades,
+
+    #[error("trade has more than one route")]
+    MultipleRoutes,
+
+    #[error("trade is not an exact input trade")]
+    NotExactInput,
+
+    #[error("amount does not fit in a signed 256-bit integer")]
+    AmountOverflow,
+
+    #[error("insufficient liquidity for the swap")]
+    InsufficientLiquidity,
+
+    #[error("protocol fee {0} is not 0 or in 4..=10")]
+    InvalidFeeProtocol(u8),
+
+    #[error("liquidity overflow")]
+    LiquidityOverflow,
+
+    #[error("liquidity underflow")]
+    LiquidityUnderflow,
+
+    #[error("quote is stale")]
+    StaleQuote,
+
+    #[error("max hops exceeds MAX_HOPS")]
+    MaxHopsExceeded,
+
+    #[error("tick {0} is already in the list")]
+    DuplicateTick(i32),
+
+    #[error("tick {0} is not a multiple of the tick spacing")]
+    TickNotAligned(i32),
+
+    #[error("fee {0} with tick spacing {1} is not a valid fee amount")]
+    InvalidFeeAmount(u32, i32),
+
+    #[error("tick spacing {0} is not positive")]
+    InvalidTickSpacing(i32),
+
+    #[error("tick {0} is out of range")]
+    TickOutOfRange(i32),
+
+    #[error("tick {0} is at the current price")]
+    TickAtCurrentPrice(i32),
+
+    #[error("liquidity gross of tick {0} is less than its absolute liquidity net")]
+    InvalidLiquidityGross(i32),
+
+    #[error("liquidity net of the ticks does not sum to zero")]
+    NonZeroLiquidityNet,
+
+    #[error("price is zero or has a zero denominator")]
+    DegeneratePrice,
+
+    #[error("tick {0} is inconsistent with the sqrt price")]
+    InconsistentTick(i32),
+
+    #[error("No tick data provider was given")]
+    NoTickDataProvider,
+
+    #[error("invalid subgraph data: {0}")]
+    InvalidSubgraphData(String),
+
+    #[error(transparent)]
+    MathError(#[from] UniswapV3MathError),
 }
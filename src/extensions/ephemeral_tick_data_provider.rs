@@ -73,6 +73,10 @@ impl TickDataProvider for EphemeralTickDataProvider {
             .ticks
             .next_initialized_tick_within_one_word(tick, lte, tick_spacing))
     }
+
+    fn has_ticks(&self) -> bool {
+        !self.ticks.is_empty()
+    }
 }
 
 impl From<EphemeralTickDataProvider> for TickListDataProvider {
@@ -3,16 +3,46 @@
 //! A Rust SDK for building applications on top of Uniswap V3.
 //! Migration from the TypeScript [Uniswap/v3-sdk](https://github.com/Uniswap/v3-sdk).
 
+mod abi;
 pub mod constants;
 pub mod entities;
 pub mod error;
+pub mod multicall;
+pub mod payments;
+pub mod swap_router;
 pub mod utils;
 
 #[cfg(feature = "extensions")]
 pub mod extensions;
 
+/// Re-exports the SDK along with `Token` and the fraction types of `uniswap-sdk-core` used in its
+/// APIs
+///
+/// ```
+/// use uniswap_v3_sdk::prelude::*;
+///
+/// let usdc = uniswap_sdk_core::token!(1, "A0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48", 6);
+/// let dai = uniswap_sdk_core::token!(1, "6B175474E89094C44Da98b954EedeAC495271d0F", 18);
+/// let slippage_tolerance = Percent::new(50, 10_000);
+/// assert_eq!(slippage_tolerance.as_fraction(), Fraction::new(1, 200));
+///
+/// let price: Price<_, _> = Price::new(dai.clone(), usdc.clone(), 10u128.pow(18), 10u128.pow(6));
+/// let amount = CurrencyAmount::from_raw_amount(dai, 10u128.pow(18)).unwrap();
+/// assert_eq!(price.quote(amount).unwrap().to_exact(), "1");
+/// ```
 pub mod prelude {
-    pub use crate::{constants::*, entities::*, utils::*};
+    pub use crate::{
+        constants::*, entities::*, multicall::*, payments::*, swap_router::*, utils::*,
+    };
+    pub use uniswap_sdk_core::entities::{
+        fractions::{
+            currency_amount::CurrencyAmount,
+            fraction::{Fraction, FractionBase},
+            percent::Percent,
+            price::Price,
+        },
+        token::Token,
+    };
 
     #[cfg(feature = "extensions")]
     pub use crate::extensions::*;
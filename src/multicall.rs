@@ -0,0 +1,46 @@
+use crate::abi::IMulticall;
+use alloy_primitives::Bytes;
+use alloy_sol_types::SolCall;
+
+/// Encodes a list of calls into a single `multicall`, or returns the call as is if there is only
+/// one
+///
+/// # Arguments
+///
+/// * `calldatas`: The encoded calls to perform
+///
+/// returns: Bytes
+pub fn encode_multicall(calldatas: Vec<Bytes>) -> Bytes {
+    if calldatas.len() == 1 {
+        calldatas[0].clone()
+    } else {
+        IMulticall::multicallCall {
+            data: calldatas.into_iter().map(Vec::from).collect(),
+        }
+        .abi_encode()
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::hex;
+
+    #[test]
+    fn works_for_a_single_calldata() {
+        let calldata: Bytes = hex!("01020304").into();
+        assert_eq!(encode_multicall(vec![calldata.clone()]), calldata);
+    }
+
+    #[test]
+    fn works_for_multiple_calldatas() {
+        let calldata = encode_multicall(vec![hex!("aaaaaaaa").into(), hex!("bbbbbbbb").into()]);
+        assert_eq!(&calldata[..4], IMulticall::multicallCall::SELECTOR);
+        let decoded = IMulticall::multicallCall::abi_decode(&calldata, true).unwrap();
+        assert_eq!(
+            decoded.data,
+            vec![hex!("aaaaaaaa").to_vec(), hex!("bbbbbbbb").to_vec()]
+        );
+    }
+}
@@ -0,0 +1,151 @@
+use crate::{abi::IPeripheryPaymentsWithFee, utils::big_int_to_u256};
+use alloy_primitives::{Address, Bytes, U256};
+use alloy_sol_types::SolCall;
+use uniswap_sdk_core::prelude::*;
+
+/// The fee to take on the output of a swap and the recipient of it
+#[derive(Clone, Debug, PartialEq)]
+pub struct FeeOptions {
+    /// The percent of the output that will be taken as a fee.
+    pub fee: Percent,
+    /// The recipient of the fee.
+    pub recipient: Address,
+}
+
+fn encode_fee_bips(fee: &Percent) -> U256 {
+    big_int_to_u256((fee.as_fraction() * Fraction::new(10000, 1)).quotient())
+}
+
+/// Encodes a call to unwrap the router's WETH9 balance and send it to the recipient
+///
+/// # Arguments
+///
+/// * `amount_minimum`: The minimum amount of WETH9 to unwrap
+/// * `recipient`: The recipient of the ETH
+/// * `fee_options`: Optional fee to take on the unwrapped amount
+///
+/// returns: Bytes
+pub fn encode_unwrap_weth9(
+    amount_minimum: U256,
+    recipient: Address,
+    fee_options: Option<FeeOptions>,
+) -> Bytes {
+    match fee_options {
+        Some(FeeOptions {
+            fee,
+            recipient: fee_recipient,
+        }) => IPeripheryPaymentsWithFee::unwrapWETH9WithFeeCall {
+            amountMinimum: amount_minimum,
+            recipient,
+            feeBips: encode_fee_bips(&fee),
+            feeRecipient: fee_recipient,
+        }
+        .abi_encode()
+        .into(),
+        None => IPeripheryPaymentsWithFee::unwrapWETH9Call {
+            amountMinimum: amount_minimum,
+            recipient,
+        }
+        .abi_encode()
+        .into(),
+    }
+}
+
+/// Encodes a call to sweep the router's balance of a token to the recipient
+///
+/// # Arguments
+///
+/// * `token`: The token to sweep
+/// * `amount_minimum`: The minimum amount of the token to sweep
+/// * `recipient`: The recipient of the token
+/// * `fee_options`: Optional fee to take on the swept amount
+///
+/// returns: Bytes
+pub fn encode_sweep_token(
+    token: Address,
+    amount_minimum: U256,
+    recipient: Address,
+    fee_options: Option<FeeOptions>,
+) -> Bytes {
+    match fee_options {
+        Some(FeeOptions {
+            fee,
+            recipient: fee_recipient,
+        }) => IPeripheryPaymentsWithFee::sweepTokenWithFeeCall {
+            token,
+            amountMinimum: amount_minimum,
+            recipient,
+            feeBips: encode_fee_bips(&fee),
+            feeRecipient: fee_recipient,
+        }
+        .abi_encode()
+        .into(),
+        None => IPeripheryPaymentsWithFee::sweepTokenCall {
+            token,
+            amountMinimum: amount_minimum,
+            recipient,
+        }
+        .abi_encode()
+        .into(),
+    }
+}
+
+/// Encodes a call to refund any ETH left in the router to the sender
+pub fn encode_refund_eth() -> Bytes {
+    IPeripheryPaymentsWithFee::refundETHCall {}
+        .abi_encode()
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{address, hex};
+    use once_cell::sync::Lazy;
+
+    const RECIPIENT: Address = address!("0000000000000000000000000000000000000003");
+    const AMOUNT: U256 = U256::from_limbs([123, 0, 0, 0]);
+    static FEE_OPTIONS: Lazy<FeeOptions> = Lazy::new(|| FeeOptions {
+        fee: Percent::new(1, 1000),
+        recipient: address!("0000000000000000000000000000000000000009"),
+    });
+
+    #[test]
+    fn test_encode_unwrap_weth9() {
+        assert_eq!(
+            encode_unwrap_weth9(AMOUNT, RECIPIENT, None).to_vec(),
+            hex!("49404b7c000000000000000000000000000000000000000000000000000000000000007b0000000000000000000000000000000000000000000000000000000000000003")
+        );
+    }
+
+    #[test]
+    fn test_encode_unwrap_weth9_with_fee() {
+        assert_eq!(
+            encode_unwrap_weth9(AMOUNT, RECIPIENT, Some(FEE_OPTIONS.clone())).to_vec(),
+            hex!("9b2c0a37000000000000000000000000000000000000000000000000000000000000007b0000000000000000000000000000000000000000000000000000000000000003000000000000000000000000000000000000000000000000000000000000000a0000000000000000000000000000000000000000000000000000000000000009")
+        );
+    }
+
+    #[test]
+    fn test_encode_sweep_token() {
+        let token = address!("0000000000000000000000000000000000000001");
+        assert_eq!(
+            encode_sweep_token(token, AMOUNT, RECIPIENT, None).to_vec(),
+            hex!("df2ab5bb0000000000000000000000000000000000000000000000000000000000000001000000000000000000000000000000000000000000000000000000000000007b0000000000000000000000000000000000000000000000000000000000000003")
+        );
+    }
+
+    #[test]
+    fn test_encode_sweep_token_with_fee() {
+        let token = address!("0000000000000000000000000000000000000001");
+        assert_eq!(
+            encode_sweep_token(token, AMOUNT, RECIPIENT, Some(FEE_OPTIONS.clone())).to_vec(),
+            hex!("e0e189a00000000000000000000000000000000000000000000000000000000000000001000000000000000000000000000000000000000000000000000000000000007b0000000000000000000000000000000000000000000000000000000000000003000000000000000000000000000000000000000000000000000000000000000a0000000000000000000000000000000000000000000000000000000000000009")
+        );
+    }
+
+    #[test]
+    fn test_encode_refund_eth() {
+        assert_eq!(encode_refund_eth().to_vec(), hex!("12210e8a"));
+    }
+}
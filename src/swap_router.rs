@@ -0,0 +1,364 @@
+use crate::{abi::ISwapRouter, prelude::*};
+use alloy_primitives::{Address, Bytes, U256};
+use alloy_sol_types::SolCall;
+use anyhow::Result;
+use uniswap_sdk_core::prelude::*;
+
+/// Options for producing the arguments to send calls to the router.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SwapOptions {
+    /// How much the execution price is allowed to move unfavorably from the trade execution price.
+    pub slippage_tolerance: Percent,
+    /// The account that should receive the output.
+    pub recipient: Address,
+    /// When the transaction expires, in epoch seconds.
+    pub deadline: U256,
+    /// The optional price limit for the trade.
+    pub sqrt_price_limit_x96: Option<U256>,
+    /// Optional information for taking a fee on output.
+    pub fee: Option<FeeOptions>,
+}
+
+/// Returns the unix time `seconds` from now, for use as [`SwapOptions::deadline`]
+///
+/// # Arguments
+///
+/// * `seconds`: How long from now the transaction is valid for
+///
+/// returns: The deadline in epoch seconds
+///
+pub fn deadline_from_now(seconds: u64) -> U256 {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .expect("system time is before the unix epoch")
+        .as_secs();
+    U256::from(now) + U256::from(seconds)
+}
+
+/// Produces the on-chain method name to call and the hex encoded parameters to pass as arguments
+/// for a given trade.
+///
+/// When the output is native ETH, the output is sent to the router and unwrapped to the
+/// recipient. When the input is native ETH, the maximum input is sent as `value`, and any unspent
+/// ETH is refunded for exact output trades.
+///
+/// # Arguments
+///
+/// * `trades`: The trades to produce call parameters for, which must share input and output
+///   currencies
+/// * `options`: Options for the call parameters
+///
+/// returns: MethodParameters
+///
+pub fn swap_call_parameters<TInput, TOutput>(
+    trades: &mut [Trade<TInput, TOutput>],
+    options: SwapOptions,
+) -> Result<MethodParameters>
+where
+    TInput: CurrencyTrait,
+    TOutput: CurrencyTrait,
+{
+    let SwapOptions {
+        slippage_tolerance,
+        recipient,
+        deadline,
+        sqrt_price_limit_x96,
+        fee,
+    } = options;
+    let sample_route = trades[0].swaps[0].route.clone();
+    let token_in = sample_route.input.wrapped();
+    let token_out = sample_route.output.wrapped();
+    let trade_type = trades[0].trade_type;
+
+    // All trades should have the same starting and ending token.
+    for trade in trades.iter() {
+        assert!(
+            trade.swaps[0].route.input.wrapped().equals(&token_in),
+            "TOKEN_IN_DIFF"
+        );
+        assert!(
+            trade.swaps[0].route.output.wrapped().equals(&token_out),
+            "TOKEN_OUT_DIFF"
+        );
+    }
+
+    let mut calldatas: Vec<Bytes> = Vec::new();
+
+    let mut total_amount_out = BigInt::zero();
+    for trade in trades.iter_mut() {
+        total_amount_out += trade
+            .minimum_amount_out(slippage_tolerance.clone(), None)?
+            .quotient();
+    }
+    let total_amount_out = big_int_to_u256(total_amount_out);
+
+    // flag for whether a refund needs to happen
+    let input_is_native = sample_route.input.is_native();
+    let must_refund = input_is_native && trade_type == TradeType::ExactOutput;
+    // flags for whether funds should be sent first to the router
+    let output_is_native = sample_route.output.is_native();
+    let router_must_custody = output_is_native || fee.is_some();
+
+    let mut total_value = U256::ZERO;
+    for trade in trades.iter_mut() {
+        total_value += trade.msg_value(slippage_tolerance.clone())?;
+    }
+
+    for trade in trades.iter_mut() {
+        for Swap {
+            route,
+            input_amount,
+            output_amount,
+        } in trade.swaps.clone()
+        {
+            let amount_in = big_int_to_u256(
+                trade
+                    .maximum_amount_in(slippage_tolerance.clone(), Some(input_amount))?
+                    .quotient(),
+            );
+            let amount_out = big_int_to_u256(
+                trade
+                    .minimum_amount_out(slippage_tolerance.clone(), Some(output_amount))?
+                    .quotient(),
+            );
+            let recipient = if router_must_custody {
+                Address::ZERO
+            } else {
+                recipient
+            };
+
+            let calldata = if route.pools.len() == 1 {
+                let token_in = route.token_path[0].address();
+                let token_out = route.token_path[1].address();
+                let fee = route.pools[0].fee.fee();
+                let sqrt_price_limit_x96 = sqrt_price_limit_x96.unwrap_or_default();
+                match trade_type {
+                    TradeType::ExactInput => ISwapRouter::exactInputSingleCall {
+                        params: ISwapRouter::ExactInputSingleParams {
+                            tokenIn: token_in,
+                            tokenOut: token_out,
+                            fee,
+                            recipient,
+                            deadline,
+                            amountIn: amount_in,
+                            amountOutMinimum: amount_out,
+                            sqrtPriceLimitX96: sqrt_price_limit_x96,
+                        },
+                    }
+                    .abi_encode(),
+                    TradeType::ExactOutput => ISwapRouter::exactOutputSingleCall {
+                        params: ISwapRouter::ExactOutputSingleParams {
+                            tokenIn: token_in,
+                            tokenOut: token_out,
+                            fee,
+                            recipient,
+                            deadline,
+                            amountOut: amount_out,
+                            amountInMaximum: amount_in,
+                            sqrtPriceLimitX96: sqrt_price_limit_x96,
+                        },
+                    }
+                    .abi_encode(),
+                }
+            } else {
+                assert!(sqrt_price_limit_x96.is_none(), "MULTIHOP_PRICE_LIMIT");
+                let path =
+                    encode_route_to_path(&route, trade_type == TradeType::ExactOutput).to_vec();
+                match trade_type {
+                    TradeType::ExactInput => ISwapRouter::exactInputCall {
+                        params: ISwapRouter::ExactInputParams {
+                            path,
+                            recipient,
+                            deadline,
+                            amountIn: amount_in,
+                            amountOutMinimum: amount_out,
+                        },
+                    }
+                    .abi_encode(),
+                    TradeType::ExactOutput => ISwapRouter::exactOutputCall {
+                        params: ISwapRouter::ExactOutputParams {
+                            path,
+                            recipient,
+                            deadline,
+                            amountOut: amount_out,
+                            amountInMaximum: amount_in,
+                        },
+                    }
+                    .abi_encode(),
+                }
+            };
+            calldatas.push(calldata.into());
+        }
+    }
+
+    // unwrap
+    if router_must_custody {
+        if output_is_native {
+            calldatas.push(encode_unwrap_weth9(total_amount_out, recipient, fee));
+        } else {
+            calldatas.push(encode_sweep_token(
+                token_out.address(),
+                total_amount_out,
+                recipient,
+                fee,
+            ));
+        }
+    }
+
+    // refund
+    if must_refund {
+        calldatas.push(encode_refund_eth());
+    }
+
+    Ok(MethodParameters {
+        calldata: encode_multicall(calldatas),
+        value: total_value,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        abi::{IMulticall, IPeripheryPaymentsWithFee},
+        entities::full_range_pool,
+    };
+    use alloy_primitives::address;
+    use once_cell::sync::Lazy;
+    use uniswap_sdk_core::token;
+
+    static ETHER: Lazy<Ether> = Lazy::new(|| Ether::on_chain(1));
+    static WETH: Lazy<Token> = Lazy::new(|| ETHER.wrapped());
+    static TOKEN0: Lazy<Token> =
+        Lazy::new(|| token!(1, "0x0000000000000000000000000000000000000001", 18, "t0"));
+    static TOKEN1: Lazy<Token> =
+        Lazy::new(|| token!(1, "0x0000000000000000000000000000000000000002", 18, "t1"));
+
+    const RECIPIENT: Address = address!("0000000000000000000000000000000000000003");
+    const DEADLINE: U256 = U256::from_limbs([123, 0, 0, 0]);
+
+    fn options() -> SwapOptions {
+        SwapOptions {
+            slippage_tolerance: Percent::new(1, 100),
+            recipient: RECIPIENT,
+            deadline: DEADLINE,
+            sqrt_price_limit_x96: None,
+            fee: None,
+        }
+    }
+
+    #[test]
+    fn deadline_from_now_adds_the_duration() {
+        let now = || {
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_secs()
+        };
+        let before = now();
+        let deadline = deadline_from_now(1800);
+        let after = now();
+        assert!(deadline >= U256::from(before + 1800));
+        assert!(deadline <= U256::from(after + 1800));
+    }
+
+    #[test]
+    fn ether_input_sets_value_without_unwrap() -> Result<()> {
+        let mut trade = Trade::exact_in(
+            Route::new(
+                vec![full_range_pool(
+                    &WETH,
+                    &TOKEN0,
+                    FeeAmount::MEDIUM,
+                    1_000_000,
+                )],
+                ETHER.clone(),
+                TOKEN0.clone(),
+            )?,
+            CurrencyAmount::from_raw_amount(ETHER.clone(), 100)?,
+        )?;
+        let amount_out_minimum = big_int_to_u256(
+            trade
+                .minimum_amount_out(Percent::new(1, 100), None)?
+                .quotient(),
+        );
+        let MethodParameters { calldata, value } = swap_call_parameters(&mut [trade], options())?;
+
+        assert_eq!(value, U256::from(100));
+        // a single call is not wrapped in a multicall
+        let call = ISwapRouter::exactInputSingleCall::abi_decode(&calldata, true)?;
+        assert_eq!(call.params.tokenIn, WETH.address());
+        assert_eq!(call.params.tokenOut, TOKEN0.address());
+        assert_eq!(call.params.recipient, RECIPIENT);
+        assert_eq!(call.params.deadline, DEADLINE);
+        assert_eq!(call.params.amountIn, U256::from(100));
+        assert_eq!(call.params.amountOutMinimum, amount_out_minimum);
+        Ok(())
+    }
+
+    #[test]
+    fn ether_output_appends_unwrap_with_zero_value() -> Result<()> {
+        let mut trade = Trade::exact_in(
+            Route::new(
+                vec![full_range_pool(
+                    &TOKEN0,
+                    &WETH,
+                    FeeAmount::MEDIUM,
+                    1_000_000,
+                )],
+                TOKEN0.clone(),
+                ETHER.clone(),
+            )?,
+            CurrencyAmount::from_raw_amount(TOKEN0.clone(), 100)?,
+        )?;
+        let amount_out_minimum = big_int_to_u256(
+            trade
+                .minimum_amount_out(Percent::new(1, 100), None)?
+                .quotient(),
+        );
+        let MethodParameters { calldata, value } = swap_call_parameters(&mut [trade], options())?;
+
+        assert_eq!(value, U256::ZERO);
+        let calls = IMulticall::multicallCall::abi_decode(&calldata, true)?.data;
+        assert_eq!(calls.len(), 2);
+        let swap = ISwapRouter::exactInputSingleCall::abi_decode(&calls[0], true)?;
+        // the router custodies the WETH before unwrapping it to the recipient
+        assert_eq!(swap.params.recipient, Address::ZERO);
+        assert_eq!(swap.params.tokenOut, WETH.address());
+        let unwrap = IPeripheryPaymentsWithFee::unwrapWETH9Call::abi_decode(&calls[1], true)?;
+        assert_eq!(unwrap.amountMinimum, amount_out_minimum);
+        assert_eq!(unwrap.recipient, RECIPIENT);
+        Ok(())
+    }
+
+    #[test]
+    fn ether_input_exact_output_appends_refund() -> Result<()> {
+        let mut trade = Trade::exact_out(
+            Route::new(
+                vec![
+                    full_range_pool(&WETH, &TOKEN0, FeeAmount::MEDIUM, 1_000_000),
+                    full_range_pool(&TOKEN0, &TOKEN1, FeeAmount::MEDIUM, 1_000_000),
+                ],
+                ETHER.clone(),
+                TOKEN1.clone(),
+            )?,
+            CurrencyAmount::from_raw_amount(TOKEN1.clone(), 100)?,
+        )?;
+        let amount_in_maximum = big_int_to_u256(
+            trade
+                .maximum_amount_in(Percent::new(1, 100), None)?
+                .quotient(),
+        );
+        let MethodParameters { calldata, value } = swap_call_parameters(&mut [trade], options())?;
+
+        assert_eq!(value, amount_in_maximum);
+        let calls = IMulticall::multicallCall::abi_decode(&calldata, true)?.data;
+        assert_eq!(calls.len(), 2);
+        let swap = ISwapRouter::exactOutputCall::abi_decode(&calls[0], true)?;
+        assert_eq!(swap.params.recipient, RECIPIENT);
+        assert_eq!(swap.params.amountOut, U256::from(100));
+        assert_eq!(swap.params.amountInMaximum, amount_in_maximum);
+        assert_eq!(calls[1], encode_refund_eth().to_vec());
+        Ok(())
+    }
+}
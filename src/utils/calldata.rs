@@ -0,0 +1,10 @@
+use alloy_primitives::{Bytes, U256};
+
+/// Generated method parameters for executing a call.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MethodParameters {
+    /// The hex encoded calldata to perform the given operation
+    pub calldata: Bytes,
+    /// The amount of ether (wei) to send.
+    pub value: U256,
+}
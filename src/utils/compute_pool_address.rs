@@ -1,6 +1,7 @@
 use crate::constants::{FeeAmount, POOL_INIT_CODE_HASH};
 use alloy_primitives::{keccak256, Address, B256};
 use alloy_sol_types::SolValue;
+use uniswap_sdk_core::prelude::{BaseCurrency, CurrencyTrait, Token};
 
 /// Computes a pool address
 ///
@@ -26,13 +27,50 @@ pub fn compute_pool_address(
     } else {
         (token_b, token_a)
     };
-    let pool_key = (token_0, token_1, fee as i32);
+    let pool_key = (token_0, token_1, fee.fee() as i32);
     factory.create2(
         keccak256(pool_key.abi_encode()),
         init_code_hash_manual_override.unwrap_or(POOL_INIT_CODE_HASH),
     )
 }
 
+/// Computes the candidate pool address of a pair for each of the default fee tiers, without
+/// checking whether the pools exist
+///
+/// # Arguments
+///
+/// * `factory`: The Uniswap V3 factory address
+/// * `token_a`: The first token of the pair, irrespective of sort order
+/// * `token_b`: The second token of the pair, irrespective of sort order
+/// * `chain_id`: The chain of the factory, which both tokens must be on
+///
+/// returns: Vec<(FeeAmount, Address)>
+pub fn pool_addresses_for_pair(
+    factory: Address,
+    token_a: &Token,
+    token_b: &Token,
+    chain_id: u32,
+) -> Vec<(FeeAmount, Address)> {
+    assert!(
+        token_a.chain_id() == chain_id && token_b.chain_id() == chain_id,
+        "CHAIN_ID"
+    );
+    [
+        FeeAmount::LOWEST,
+        FeeAmount::LOW,
+        FeeAmount::MEDIUM,
+        FeeAmount::HIGH,
+    ]
+    .into_iter()
+    .map(|fee| {
+        (
+            fee,
+            compute_pool_address(factory, token_a.address(), token_b.address(), fee, None),
+        )
+    })
+    .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -63,4 +101,47 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    fn test_compute_pool_address_for_custom_fee() {
+        // the address only depends on the fee, not on the tick spacing
+        assert_eq!(
+            compute_pool_address(
+                FACTORY_ADDRESS,
+                USDC_ADDRESS,
+                DAI_ADDRESS,
+                FeeAmount::Custom(500, 5),
+                None,
+            ),
+            address!("90B1b09A9715CaDbFD9331b3A7652B24BfBEfD32")
+        );
+        assert_ne!(
+            compute_pool_address(
+                FACTORY_ADDRESS,
+                USDC_ADDRESS,
+                DAI_ADDRESS,
+                FeeAmount::Custom(250, 5),
+                None,
+            ),
+            address!("90B1b09A9715CaDbFD9331b3A7652B24BfBEfD32")
+        );
+    }
+
+    #[test]
+    fn test_pool_addresses_for_pair() {
+        let usdc = Token::new(1, USDC_ADDRESS.to_string(), 6, None, None, None, None);
+        let dai = Token::new(1, DAI_ADDRESS.to_string(), 18, None, None, None, None);
+        let addresses = pool_addresses_for_pair(FACTORY_ADDRESS, &usdc, &dai, 1);
+        assert_eq!(addresses.len(), 4);
+        assert_eq!(
+            addresses[1],
+            (
+                FeeAmount::LOW,
+                address!("90B1b09A9715CaDbFD9331b3A7652B24BfBEfD32")
+            )
+        );
+        for (i, (_, address)) in addresses.iter().enumerate() {
+            assert!(!addresses[..i].iter().any(|(_, other)| other == address));
+        }
+    }
 }
@@ -0,0 +1,149 @@
+use crate::entities::Route;
+use alloy_primitives::Bytes;
+use uniswap_sdk_core::prelude::*;
+
+/// Converts a route to a hex encoded path
+///
+/// # Arguments
+///
+/// * `route`: The route to convert to an encoded path
+/// * `exact_output`: Whether the route should be encoded in reverse, for making exact output swaps
+///
+/// returns: Bytes
+pub fn encode_route_to_path<TInput, TOutput>(
+    route: &Route<TInput, TOutput>,
+    exact_output: bool,
+) -> Bytes
+where
+    TInput: CurrencyTrait,
+    TOutput: CurrencyTrait,
+{
+    let mut path = Vec::with_capacity(20 + route.pools.len() * 23);
+    let mut push_hop = |token: &Token, fee: Option<u32>| {
+        if let Some(fee) = fee {
+            path.extend_from_slice(&fee.to_be_bytes()[1..]);
+        }
+        path.extend_from_slice(token.address().as_slice());
+    };
+    if exact_output {
+        push_hop(route.token_path.last().unwrap(), None);
+        for (pool, token) in route.pools.iter().zip(&route.token_path).rev() {
+            push_hop(token, Some(pool.fee.fee()));
+        }
+    } else {
+        push_hop(&route.token_path[0], None);
+        for (pool, token) in route.pools.iter().zip(&route.token_path[1..]) {
+            push_hop(token, Some(pool.fee.fee()));
+        }
+    }
+    path.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{entities::full_range_pool, prelude::*};
+    use alloy_primitives::bytes;
+    use once_cell::sync::Lazy;
+    use uniswap_sdk_core::token;
+
+    static ETHER: Lazy<Ether> = Lazy::new(|| Ether::on_chain(1));
+    static TOKEN0: Lazy<Token> =
+        Lazy::new(|| token!(1, "0x0000000000000000000000000000000000000001", 18, "t0"));
+    static TOKEN1: Lazy<Token> =
+        Lazy::new(|| token!(1, "0x0000000000000000000000000000000000000002", 18, "t1"));
+    static TOKEN2: Lazy<Token> =
+        Lazy::new(|| token!(1, "0x0000000000000000000000000000000000000003", 18, "t2"));
+    static WETH: Lazy<Token> = Lazy::new(|| ETHER.wrapped());
+
+    #[test]
+    fn packs_single_hop_exact_input() {
+        let route = Route::new(
+            vec![full_range_pool(&TOKEN0, &TOKEN1, FeeAmount::MEDIUM, 0)],
+            TOKEN0.clone(),
+            TOKEN1.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            encode_route_to_path(&route, false),
+            bytes!("0000000000000000000000000000000000000001000bb80000000000000000000000000000000000000002")
+        );
+    }
+
+    #[test]
+    fn packs_single_hop_exact_output() {
+        let route = Route::new(
+            vec![full_range_pool(&TOKEN0, &TOKEN1, FeeAmount::MEDIUM, 0)],
+            TOKEN0.clone(),
+            TOKEN1.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            encode_route_to_path(&route, true),
+            bytes!("0000000000000000000000000000000000000002000bb80000000000000000000000000000000000000001")
+        );
+    }
+
+    #[test]
+    fn packs_multi_hop_exact_input_and_output() {
+        let route = Route::new(
+            vec![
+                full_range_pool(&TOKEN0, &TOKEN1, FeeAmount::MEDIUM, 0),
+                full_range_pool(&TOKEN1, &TOKEN2, FeeAmount::MEDIUM, 0),
+            ],
+            TOKEN0.clone(),
+            TOKEN2.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            encode_route_to_path(&route, false),
+            bytes!("0000000000000000000000000000000000000001000bb80000000000000000000000000000000000000002000bb80000000000000000000000000000000000000003")
+        );
+        assert_eq!(
+            encode_route_to_path(&route, true),
+            bytes!("0000000000000000000000000000000000000003000bb80000000000000000000000000000000000000002000bb80000000000000000000000000000000000000001")
+        );
+    }
+
+    #[test]
+    fn packs_custom_fee_tier() {
+        let custom = Pool::new(
+            TOKEN1.clone(),
+            TOKEN2.clone(),
+            FeeAmount::Custom(250, 5),
+            encode_sqrt_ratio_x96(1, 1),
+            0,
+            None,
+        )
+        .unwrap();
+        let route = Route::new(
+            vec![
+                full_range_pool(&TOKEN0, &TOKEN1, FeeAmount::MEDIUM, 0),
+                custom,
+            ],
+            TOKEN0.clone(),
+            TOKEN2.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            encode_route_to_path(&route, false),
+            bytes!("0000000000000000000000000000000000000001000bb800000000000000000000000000000000000000020000fa0000000000000000000000000000000000000003")
+        );
+    }
+
+    #[test]
+    fn wraps_ether_input() {
+        let route = Route::new(
+            vec![
+                full_range_pool(&WETH, &TOKEN0, FeeAmount::MEDIUM, 0),
+                full_range_pool(&TOKEN0, &TOKEN1, FeeAmount::MEDIUM, 0),
+            ],
+            ETHER.clone(),
+            TOKEN1.clone(),
+        )
+        .unwrap();
+        let path = encode_route_to_path(&route, false);
+        assert_eq!(&path[..20], WETH.address().as_slice());
+        assert_eq!(&path[46..], TOKEN1.address().as_slice());
+    }
+}
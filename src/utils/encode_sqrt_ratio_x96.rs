@@ -1,4 +1,4 @@
-use super::big_int_to_u256;
+use super::{big_int_to_u256, u256_to_big_int};
 use alloy_primitives::U256;
 use num_bigint::BigInt;
 use uniswap_sdk_core::utils::sqrt::sqrt;
@@ -18,6 +18,42 @@ pub fn encode_sqrt_ratio_x96(amount1: impl Into<BigInt>, amount0: impl Into<BigI
     big_int_to_u256(sqrt(&(numerator / denominator)).unwrap())
 }
 
+/// Returns the sqrt ratio as a Q64.96 corresponding to a given ratio of amount1 and amount0, rounded to the nearest
+/// value rather than down as in [`encode_sqrt_ratio_x96`]. The square root is taken with 64 extra bits of precision
+/// before rounding, so the result is the closest Q64.96 to the exact square root of the ratio.
+///
+/// # Arguments
+///
+/// * `amount1`: The numerator amount i.e., the amount of token1
+/// * `amount0`: The denominator amount i.e., the amount of token0
+///
+/// returns: U256 The sqrt ratio as a Q64.96
+///
+pub fn encode_sqrt_ratio_x96_precise(
+    amount1: impl Into<BigInt>,
+    amount0: impl Into<BigInt>,
+) -> U256 {
+    const EXTRA_BITS: u32 = 64;
+    let numerator: BigInt = amount1.into() << (192 + 2 * EXTRA_BITS);
+    let denominator = amount0.into();
+    let sqrt_ratio = sqrt(&(numerator / denominator)).unwrap();
+    big_int_to_u256((sqrt_ratio + (BigInt::from(1) << (EXTRA_BITS - 1))) >> EXTRA_BITS)
+}
+
+/// Returns the sqrt ratio as a Q64.96 corresponding to a given ratio of amount1 and amount0, where the amounts
+/// are full-width `U256` values such as raw pool reserves
+///
+/// # Arguments
+///
+/// * `amount1`: The numerator amount i.e., the amount of token1
+/// * `amount0`: The denominator amount i.e., the amount of token0
+///
+/// returns: U256 The sqrt ratio as a Q64.96
+///
+pub fn encode_sqrt_ratio_x96_u256(amount1: U256, amount0: U256) -> U256 {
+    encode_sqrt_ratio_x96(u256_to_big_int(amount1), u256_to_big_int(amount0))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -43,4 +79,51 @@ mod tests {
             U256::from(137227202865029797602485611888u128)
         );
     }
+
+    #[test]
+    fn test_encode_sqrt_ratio_x96_precise() {
+        // the distance of the squared sqrt ratio from the exact ratio, scaled by amount0
+        let error = |sqrt_ratio: U256, amount1: u64, amount0: u64| {
+            let sqrt_ratio = u256_to_big_int(sqrt_ratio);
+            let error: BigInt =
+                &sqrt_ratio * &sqrt_ratio * amount0 - (BigInt::from(amount1) << 192);
+            error.magnitude().clone()
+        };
+        for (amount1, amount0) in [(3, 7), (1, 7), (2, 1), (3, 5)] {
+            let floor = encode_sqrt_ratio_x96(amount1, amount0);
+            let precise = encode_sqrt_ratio_x96_precise(amount1, amount0);
+            assert!(precise - floor <= U256::from(1));
+            assert!(error(precise, amount1, amount0) <= error(floor, amount1, amount0));
+        }
+        // rounding to the nearest value is strictly closer when the floor is not the nearest
+        assert!(
+            error(encode_sqrt_ratio_x96_precise(2, 1), 2, 1)
+                < error(encode_sqrt_ratio_x96(2, 1), 2, 1)
+        );
+        assert_eq!(encode_sqrt_ratio_x96_precise(1, 1), Q96);
+        assert_eq!(
+            encode_sqrt_ratio_x96_precise(100, 1),
+            encode_sqrt_ratio_x96(100, 1)
+        );
+    }
+
+    #[test]
+    fn test_encode_sqrt_ratio_x96_u256() {
+        for (amount1, amount0) in [(1, 1), (100, 1), (1, 100), (111, 333), (333, 111)] {
+            assert_eq!(
+                encode_sqrt_ratio_x96_u256(U256::from(amount1), U256::from(amount0)),
+                encode_sqrt_ratio_x96(amount1, amount0)
+            );
+        }
+        let scale = U256::from(10).pow(U256::from(50));
+        assert_eq!(
+            encode_sqrt_ratio_x96_u256(U256::from(100) * scale, scale),
+            encode_sqrt_ratio_x96(100, 1)
+        );
+        assert_eq!(encode_sqrt_ratio_x96_u256(U256::MAX, U256::MAX), Q96);
+        assert_eq!(
+            encode_sqrt_ratio_x96_u256(U256::MAX, U256::from(1)),
+            (U256::from(1) << 224) - U256::from(1)
+        );
+    }
 }
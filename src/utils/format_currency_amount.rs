@@ -0,0 +1,80 @@
+use std::num::NonZeroU64;
+use uniswap_sdk_core::prelude::*;
+
+/// Formats an amount for display, rounded half up to `significant_digits` significant digits, with
+/// the thousands of the integer part separated by commas and followed by the token symbol, e.g.
+/// `1,234.5678 USDC`
+///
+/// # Arguments
+///
+/// * `amount`: The amount to format
+/// * `significant_digits`: The number of significant digits to keep, which must not be zero
+///
+/// returns: String
+///
+pub fn format_currency_amount(amount: &CurrencyAmount<Token>, significant_digits: u8) -> String {
+    let significant_digits =
+        NonZeroU64::new(significant_digits as u64).expect("SIGNIFICANT_DIGITS");
+    let value = BigDecimal::new(amount.quotient(), amount.meta.currency.decimals() as i64)
+        .with_precision_round(significant_digits, RoundingMode::HalfUp)
+        .normalized();
+    let scale = value.as_bigint_and_exponent().1.max(0) as usize;
+    let formatted = format!("{:.1$}", value, scale);
+
+    let (integer, fraction) = match formatted.split_once('.') {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (formatted.as_str(), None),
+    };
+    let (sign, digits) = match integer.strip_prefix('-') {
+        Some(digits) => ("-", digits),
+        None => ("", integer),
+    };
+    let mut grouped = String::with_capacity(formatted.len() + digits.len() / 3 + 8);
+    grouped.push_str(sign);
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    if let Some(fraction) = fraction {
+        grouped.push('.');
+        grouped.push_str(fraction);
+    }
+    match amount.meta.currency.symbol() {
+        Some(symbol) => format!("{grouped} {symbol}"),
+        None => grouped,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uniswap_sdk_core::token;
+
+    #[test]
+    fn test_format_currency_amount() {
+        let usdc = token!(
+            1,
+            "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48",
+            6,
+            "USDC",
+            "USD Coin"
+        );
+        let amount = |raw: u128| CurrencyAmount::from_raw_amount(usdc.clone(), raw).unwrap();
+        assert_eq!(
+            format_currency_amount(&amount(1_234_567_800), 8),
+            "1,234.5678 USDC"
+        );
+        assert_eq!(
+            format_currency_amount(&amount(1_234_567_800), 6),
+            "1,234.57 USDC"
+        );
+        assert_eq!(
+            format_currency_amount(&amount(1_234_567_891_000_000), 4),
+            "1,235,000,000 USDC"
+        );
+        assert_eq!(format_currency_amount(&amount(120), 4), "0.00012 USDC");
+        assert_eq!(format_currency_amount(&amount(0), 4), "0 USDC");
+    }
+}
@@ -1,5 +1,50 @@
-use uniswap_v3_math::{error::UniswapV3MathError, liquidity_math};
+use crate::error::Error;
 
-pub fn add_delta(x: u128, y: i128) -> Result<u128, UniswapV3MathError> {
-    liquidity_math::add_delta(x, y)
+/// Add a signed liquidity delta to liquidity and revert if it overflows or underflows
+///
+/// # Arguments
+///
+/// * `x`: The liquidity before change
+/// * `y`: The delta by which liquidity should be changed
+///
+/// returns: Result<u128, Error>
+pub fn add_delta(x: u128, y: i128) -> Result<u128, Error> {
+    if y < 0 {
+        x.checked_sub(y.unsigned_abs())
+            .ok_or(Error::LiquidityUnderflow)
+    } else {
+        x.checked_add(y as u128).ok_or(Error::LiquidityOverflow)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_delta() {
+        assert_eq!(add_delta(1, 0).unwrap(), 1);
+        assert_eq!(add_delta(1, -1).unwrap(), 0);
+        assert_eq!(add_delta(1, 1).unwrap(), 2);
+        assert_eq!(add_delta(0, i128::MAX).unwrap(), i128::MAX as u128);
+        assert_eq!(add_delta(u128::MAX, i128::MIN).unwrap(), u128::MAX >> 1);
+    }
+
+    #[test]
+    fn test_add_delta_underflow() {
+        assert!(matches!(add_delta(0, -1), Err(Error::LiquidityUnderflow)));
+        assert!(matches!(add_delta(3, -4), Err(Error::LiquidityUnderflow)));
+    }
+
+    #[test]
+    fn test_add_delta_overflow() {
+        assert!(matches!(
+            add_delta(u128::MAX - 14, 15),
+            Err(Error::LiquidityOverflow)
+        ));
+        assert!(matches!(
+            add_delta(u128::MAX, 1),
+            Err(Error::LiquidityOverflow)
+        ));
+    }
 }
@@ -1,4 +1,5 @@
-use super::u256_to_big_uint;
+use super::{get_amount_0_delta, get_amount_1_delta, u256_to_big_uint};
+use crate::error::Error;
 use alloy_primitives::U256;
 use num_bigint::BigUint;
 
@@ -94,7 +95,7 @@ pub fn max_liquidity_for_amount1(
 /// * `amount0`: The token0 amount
 /// * `amount1`: The token1 amount
 /// * `use_full_precision`: if false, liquidity will be maximized according to what the router can calculate,
-/// not what core can theoretically support
+///   not what core can theoretically support
 ///
 /// returns: maximum liquidity for the given amounts
 ///
@@ -135,6 +136,44 @@ pub fn max_liquidity_for_amounts(
     }
 }
 
+/// Computes the amount of token0 for a given amount of liquidity between two prices, rounded down,
+/// as LiquidityAmounts#getAmount0ForLiquidity in the v3 periphery
+///
+/// # Arguments
+///
+/// * `sqrt_ratio_a_x96`: The price at the lower boundary
+/// * `sqrt_ratio_b_x96`: The price at the upper boundary
+/// * `liquidity`: The liquidity being valued
+///
+/// returns: the amount of token0
+///
+pub fn get_amount0_for_liquidity(
+    sqrt_ratio_a_x96: U256,
+    sqrt_ratio_b_x96: U256,
+    liquidity: u128,
+) -> Result<U256, Error> {
+    get_amount_0_delta(sqrt_ratio_a_x96, sqrt_ratio_b_x96, liquidity, false)
+}
+
+/// Computes the amount of token1 for a given amount of liquidity between two prices, rounded down,
+/// as LiquidityAmounts#getAmount1ForLiquidity in the v3 periphery
+///
+/// # Arguments
+///
+/// * `sqrt_ratio_a_x96`: The price at the lower boundary
+/// * `sqrt_ratio_b_x96`: The price at the upper boundary
+/// * `liquidity`: The liquidity being valued
+///
+/// returns: the amount of token1
+///
+pub fn get_amount1_for_liquidity(
+    sqrt_ratio_a_x96: U256,
+    sqrt_ratio_b_x96: U256,
+    liquidity: u128,
+) -> Result<U256, Error> {
+    get_amount_1_delta(sqrt_ratio_a_x96, sqrt_ratio_b_x96, liquidity, false)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -424,4 +463,34 @@ mod tests {
             2097u64.into()
         );
     }
+
+    #[test]
+    fn amounts_for_liquidity_round_trip() {
+        let sqrt_ratio_a_x96 = encode_sqrt_ratio_x96(100, 110);
+        let sqrt_ratio_b_x96 = encode_sqrt_ratio_x96(110, 100);
+        let liquidity = 1_000_000_000_000_000_000u128;
+
+        let amount0 =
+            get_amount0_for_liquidity(sqrt_ratio_a_x96, sqrt_ratio_b_x96, liquidity).unwrap();
+        let liquidity0 =
+            max_liquidity_for_amount0_precise(sqrt_ratio_a_x96, sqrt_ratio_b_x96, amount0);
+        assert!(liquidity0 <= BigUint::from(liquidity));
+        // one more wei of token0 would be worth more than the original liquidity
+        assert!(
+            max_liquidity_for_amount0_precise(
+                sqrt_ratio_a_x96,
+                sqrt_ratio_b_x96,
+                amount0 + U256::from(1)
+            ) > BigUint::from(liquidity)
+        );
+
+        let amount1 =
+            get_amount1_for_liquidity(sqrt_ratio_b_x96, sqrt_ratio_a_x96, liquidity).unwrap();
+        let liquidity1 = max_liquidity_for_amount1(sqrt_ratio_a_x96, sqrt_ratio_b_x96, amount1);
+        assert!(liquidity1 <= BigUint::from(liquidity));
+        assert!(
+            max_liquidity_for_amount1(sqrt_ratio_a_x96, sqrt_ratio_b_x96, amount1 + U256::from(1))
+                > BigUint::from(liquidity)
+        );
+    }
 }
@@ -1,6 +1,9 @@
 mod bit_math;
+mod calldata;
 mod compute_pool_address;
+mod encode_route_to_path;
 mod encode_sqrt_ratio_x96;
+mod format_currency_amount;
 mod full_math;
 mod get_fee_growth_inside;
 mod get_tokens_owed;
@@ -14,8 +17,13 @@ mod tick_list;
 mod tick_math;
 
 pub use bit_math::*;
-pub use compute_pool_address::compute_pool_address;
-pub use encode_sqrt_ratio_x96::encode_sqrt_ratio_x96;
+pub use calldata::MethodParameters;
+pub use compute_pool_address::{compute_pool_address, pool_addresses_for_pair};
+pub use encode_route_to_path::encode_route_to_path;
+pub use encode_sqrt_ratio_x96::{
+    encode_sqrt_ratio_x96, encode_sqrt_ratio_x96_precise, encode_sqrt_ratio_x96_u256,
+};
+pub use format_currency_amount::format_currency_amount;
 pub use full_math::*;
 pub use get_fee_growth_inside::*;
 pub use get_tokens_owed::get_tokens_owed;
@@ -32,6 +40,7 @@ use alloy_primitives::{I256, U256};
 use num_bigint::{BigInt, BigUint, Sign};
 use num_traits::{Signed, ToBytes};
 use std::ops::Neg;
+use uniswap_sdk_core::prelude::Percent;
 
 pub const Q96: U256 = U256::from_limbs([0, 4294967296, 0, 0]);
 pub const Q128: U256 = U256::from_limbs([0, 0, 1, 0]);
@@ -68,3 +77,9 @@ pub fn big_int_to_i256(x: BigInt) -> I256 {
         I256::from_raw(big_int_to_u256(x.neg())).neg()
     }
 }
+
+/// Returns the slippage tolerance of the given number of basis points, e.g. 50 for 0.5%, which can
+/// be passed wherever a slippage tolerance is taken
+pub fn slippage_from_bps(bps: u32) -> Percent {
+    Percent::new(bps, 10_000)
+}
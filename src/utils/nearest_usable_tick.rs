@@ -3,6 +3,9 @@ use num_integer::Integer;
 
 /// Returns the closest tick that is nearest a given tick and usable for the given tick spacing
 ///
+/// Like `nearestUsableTick` in the TypeScript SDK, which uses `Math.round`, a tick halfway between
+/// two usable ticks rounds towards positive infinity, e.g. -5 rounds to 0 for a spacing of 10.
+///
 /// # Arguments
 ///
 /// * `tick`: the target tick
@@ -27,6 +30,20 @@ pub fn nearest_usable_tick(tick: i32, tick_spacing: i32) -> i32 {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn result_is_a_usable_tick_within_bounds(
+            tick in MIN_TICK..=MAX_TICK,
+            tick_spacing in 1..=MAX_TICK,
+        ) {
+            let usable_tick = nearest_usable_tick(tick, tick_spacing);
+            prop_assert_eq!(usable_tick % tick_spacing, 0);
+            prop_assert!((MIN_TICK..=MAX_TICK).contains(&usable_tick));
+            prop_assert!((usable_tick - tick).abs() <= tick_spacing);
+        }
+    }
 
     #[test]
     #[should_panic(expected = "TICK_SPACING")]
@@ -72,6 +89,17 @@ mod tests {
         assert_eq!(nearest_usable_tick(-6, 10), -10);
     }
 
+    #[test]
+    fn matches_math_round_for_both_signs() {
+        // `Math.round` in the TypeScript SDK rounds halves towards positive infinity
+        assert_eq!(nearest_usable_tick(-5, 10), 0);
+        assert_eq!(nearest_usable_tick(-7, 10), -10);
+        assert_eq!(nearest_usable_tick(5, 10), 10);
+        assert_eq!(nearest_usable_tick(7, 10), 10);
+        assert_eq!(nearest_usable_tick(-15, 10), -10);
+        assert_eq!(nearest_usable_tick(15, 10), 20);
+    }
+
     #[test]
     fn cannot_round_past_min_tick() {
         assert_eq!(
@@ -3,15 +3,16 @@
 //! with custom optimizations presented in [uni-v3-lib](https://github.com/Aperture-Finance/uni-v3-lib/blob/main/src/SqrtPriceMath.sol).
 
 use super::{mul_div, mul_div_96, mul_div_rounding_up, Q96};
+use crate::error::Error;
 use alloy_primitives::{I256, U256};
 use uniswap_v3_math::error::UniswapV3MathError;
 
 const MAX_U160: U256 =
     U256::from_limbs([18446744073709551615, 18446744073709551615, 4294967295, 0]);
 
-fn to_uint160(x: U256) -> Result<U256, UniswapV3MathError> {
+fn to_uint160(x: U256) -> Result<U256, Error> {
     if x > MAX_U160 {
-        Err(UniswapV3MathError::SafeCastToU160Overflow)
+        Err(UniswapV3MathError::SafeCastToU160Overflow.into())
     } else {
         Ok(x)
     }
@@ -43,7 +44,7 @@ pub fn get_next_sqrt_price_from_amount_0_rounding_up(
     liquidity: u128,
     amount: U256,
     add: bool,
-) -> Result<U256, UniswapV3MathError> {
+) -> Result<U256, Error> {
     if amount.is_zero() {
         return Ok(sqrt_price_x96);
     }
@@ -55,7 +56,11 @@ pub fn get_next_sqrt_price_from_amount_0_rounding_up(
         if product / amount == sqrt_price_x96 {
             let denominator = numerator_1 + product;
             if denominator >= numerator_1 {
-                return mul_div_rounding_up(numerator_1, sqrt_price_x96, denominator);
+                return Ok(mul_div_rounding_up(
+                    numerator_1,
+                    sqrt_price_x96,
+                    denominator,
+                )?);
             }
         }
 
@@ -63,7 +68,7 @@ pub fn get_next_sqrt_price_from_amount_0_rounding_up(
     } else {
         let product = amount * sqrt_price_x96;
         if !(product / amount == sqrt_price_x96 && numerator_1 > product) {
-            Err(UniswapV3MathError::ProductDivAmount)
+            Err(UniswapV3MathError::ProductDivAmount.into())
         } else {
             let denominator = numerator_1 - product;
 
@@ -97,7 +102,7 @@ pub fn get_next_sqrt_price_from_amount_1_rounding_down(
     liquidity: u128,
     amount: U256,
     add: bool,
-) -> Result<U256, UniswapV3MathError> {
+) -> Result<U256, Error> {
     if add {
         let quotient = if amount <= MAX_U160 {
             (amount << 96) / to_uint256(liquidity)
@@ -117,7 +122,7 @@ pub fn get_next_sqrt_price_from_amount_1_rounding_down(
         if sqrt_price_x96 > quotient {
             Ok(sqrt_price_x96 - quotient)
         } else {
-            Err(UniswapV3MathError::SqrtPriceIsLteQuotient)
+            Err(UniswapV3MathError::SqrtPriceIsLteQuotient.into())
         }
     }
 }
@@ -140,11 +145,11 @@ pub fn get_next_sqrt_price_from_input(
     liquidity: u128,
     amount_in: U256,
     zero_for_one: bool,
-) -> Result<U256, UniswapV3MathError> {
+) -> Result<U256, Error> {
     if sqrt_price_x96.is_zero() {
-        return Err(UniswapV3MathError::SqrtPriceIsZero);
+        return Err(UniswapV3MathError::SqrtPriceIsZero.into());
     } else if liquidity == 0 {
-        return Err(UniswapV3MathError::LiquidityIsZero);
+        return Err(UniswapV3MathError::LiquidityIsZero.into());
     }
 
     if zero_for_one {
@@ -172,11 +177,11 @@ pub fn get_next_sqrt_price_from_output(
     liquidity: u128,
     amount_out: U256,
     zero_for_one: bool,
-) -> Result<U256, UniswapV3MathError> {
+) -> Result<U256, Error> {
     if sqrt_price_x96.is_zero() {
-        return Err(UniswapV3MathError::SqrtPriceIsZero);
+        return Err(UniswapV3MathError::SqrtPriceIsZero.into());
     } else if liquidity == 0 {
-        return Err(UniswapV3MathError::LiquidityIsZero);
+        return Err(UniswapV3MathError::LiquidityIsZero.into());
     }
 
     if zero_for_one {
@@ -210,7 +215,7 @@ pub fn get_amount_0_delta(
     mut sqrt_ratio_b_x96: U256,
     liquidity: u128,
     round_up: bool,
-) -> Result<U256, UniswapV3MathError> {
+) -> Result<U256, Error> {
     if sqrt_ratio_a_x96 > sqrt_ratio_b_x96 {
         (sqrt_ratio_a_x96, sqrt_ratio_b_x96) = (sqrt_ratio_b_x96, sqrt_ratio_a_x96);
     }
@@ -219,7 +224,7 @@ pub fn get_amount_0_delta(
     let numerator_2 = sqrt_ratio_b_x96 - sqrt_ratio_a_x96;
 
     if sqrt_ratio_a_x96.is_zero() {
-        return Err(UniswapV3MathError::SqrtPriceIsZero);
+        return Err(UniswapV3MathError::SqrtPriceIsZero.into());
     }
 
     let (amount_0, rem) =
@@ -247,7 +252,7 @@ pub fn get_amount_1_delta(
     mut sqrt_ratio_b_x96: U256,
     liquidity: u128,
     round_up: bool,
-) -> Result<U256, UniswapV3MathError> {
+) -> Result<U256, Error> {
     if sqrt_ratio_a_x96 > sqrt_ratio_b_x96 {
         (sqrt_ratio_a_x96, sqrt_ratio_b_x96) = (sqrt_ratio_b_x96, sqrt_ratio_a_x96);
     }
@@ -275,7 +280,7 @@ pub fn get_amount_0_delta_signed(
     sqrt_ratio_a_x96: U256,
     sqrt_ratio_b_x96: U256,
     liquidity: i128,
-) -> Result<I256, UniswapV3MathError> {
+) -> Result<I256, Error> {
     let sign = !liquidity.is_negative();
     let mask = (sign as u128).wrapping_sub(1);
     let liquidity = mask ^ mask.wrapping_add_signed(liquidity);
@@ -304,7 +309,7 @@ pub fn get_amount_1_delta_signed(
     sqrt_ratio_a_x96: U256,
     sqrt_ratio_b_x96: U256,
     liquidity: i128,
-) -> Result<I256, UniswapV3MathError> {
+) -> Result<I256, Error> {
     let sign = !liquidity.is_negative();
     let mask = (sign as u128).wrapping_sub(1);
     let liquidity = mask ^ mask.wrapping_add_signed(liquidity);
@@ -351,7 +356,7 @@ mod tests {
             .collect()
     }
 
-    fn match_u256(res: Result<U256, UniswapV3MathError>, ref_: Result<U256, UniswapV3MathError>) {
+    fn match_u256(res: Result<U256, Error>, ref_: Result<U256, UniswapV3MathError>) {
         match res {
             Ok(res) => {
                 assert_eq!(res, ref_.unwrap());
@@ -473,4 +478,135 @@ mod tests {
             }
         }
     }
+
+    const ONE_ETHER: u128 = 1_000_000_000_000_000_000;
+
+    fn price_1_1() -> U256 {
+        Q96
+    }
+
+    fn price_121_100() -> U256 {
+        U256::from(87150978765690771352898345369_u128)
+    }
+
+    #[test]
+    fn test_get_next_sqrt_price_from_input_vectors() {
+        assert!(matches!(
+            get_next_sqrt_price_from_input(U256::ZERO, 1, U256::from(ONE_ETHER / 10), false),
+            Err(Error::MathError(UniswapV3MathError::SqrtPriceIsZero))
+        ));
+        assert!(matches!(
+            get_next_sqrt_price_from_input(U256::from(1), 0, U256::from(ONE_ETHER / 10), true),
+            Err(Error::MathError(UniswapV3MathError::LiquidityIsZero))
+        ));
+        // returns input price if amount in is zero
+        assert_eq!(
+            get_next_sqrt_price_from_input(price_1_1(), ONE_ETHER / 10, U256::ZERO, true).unwrap(),
+            price_1_1()
+        );
+        // any input amount cannot underflow the price
+        assert_eq!(
+            get_next_sqrt_price_from_input(U256::from(1), 1, U256::from(1) << 255, true).unwrap(),
+            U256::from(1)
+        );
+        // input amount of 0.1 token1
+        assert_eq!(
+            get_next_sqrt_price_from_input(
+                price_1_1(),
+                ONE_ETHER,
+                U256::from(ONE_ETHER / 10),
+                false
+            )
+            .unwrap(),
+            price_121_100()
+        );
+        // input amount of 0.1 token0
+        assert_eq!(
+            get_next_sqrt_price_from_input(
+                price_1_1(),
+                ONE_ETHER,
+                U256::from(ONE_ETHER / 10),
+                true
+            )
+            .unwrap(),
+            U256::from(72025602285694852357767227579_u128)
+        );
+        // amountIn > type(uint96).max and zeroForOne = true
+        assert_eq!(
+            get_next_sqrt_price_from_input(price_1_1(), 10 * ONE_ETHER, U256::from(1) << 100, true)
+                .unwrap(),
+            U256::from(624999999995069620_u128)
+        );
+        // can return 1 with enough amountIn and zeroForOne = true
+        assert_eq!(
+            get_next_sqrt_price_from_input(price_1_1(), 1, U256::MAX >> 1, true).unwrap(),
+            U256::from(1)
+        );
+    }
+
+    #[test]
+    fn test_get_next_sqrt_price_from_output_vectors() {
+        let price = U256::from_str_radix("20282409603651670423947251286016", 10).unwrap();
+        // fails if output amount is exactly the virtual reserves of token0
+        assert!(get_next_sqrt_price_from_output(price, 1024, U256::from(4), false).is_err());
+        // succeeds if output amount is just less than the virtual reserves of token1
+        assert_eq!(
+            get_next_sqrt_price_from_output(price, 1024, U256::from(262143), true).unwrap(),
+            U256::from(77371252455336267181195264_u128)
+        );
+        // output amount of 0.1 token1
+        assert_eq!(
+            get_next_sqrt_price_from_output(
+                price_1_1(),
+                ONE_ETHER,
+                U256::from(ONE_ETHER / 10),
+                false
+            )
+            .unwrap(),
+            U256::from(88031291682515930659493278152_u128)
+        );
+        // output amount of 0.1 token0
+        assert_eq!(
+            get_next_sqrt_price_from_output(
+                price_1_1(),
+                ONE_ETHER,
+                U256::from(ONE_ETHER / 10),
+                true
+            )
+            .unwrap(),
+            U256::from(71305346262837903834189555302_u128)
+        );
+    }
+
+    #[test]
+    fn test_get_amount_0_delta_vectors() {
+        assert_eq!(
+            get_amount_0_delta(price_1_1(), price_121_100(), 0, true).unwrap(),
+            U256::ZERO
+        );
+        assert_eq!(
+            get_amount_0_delta(price_1_1(), price_121_100(), ONE_ETHER, true).unwrap(),
+            U256::from(90909090909090910_u128)
+        );
+        assert_eq!(
+            get_amount_0_delta(price_1_1(), price_121_100(), ONE_ETHER, false).unwrap(),
+            U256::from(90909090909090909_u128)
+        );
+    }
+
+    #[test]
+    fn test_get_amount_1_delta_vectors() {
+        assert_eq!(
+            get_amount_1_delta(price_1_1(), price_121_100(), 0, true).unwrap(),
+            U256::ZERO
+        );
+        assert_eq!(
+            get_amount_1_delta(price_1_1(), price_121_100(), ONE_ETHER, true).unwrap(),
+            U256::from(100000000000000000_u128)
+        );
+        assert_eq!(
+            get_amount_1_delta(price_1_1(), price_121_100(), ONE_ETHER, false).unwrap(),
+            U256::from(99999999999999999_u128)
+        );
+    }
 }
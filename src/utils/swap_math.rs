@@ -2,8 +2,8 @@ use super::{
     get_amount_0_delta, get_amount_1_delta, get_next_sqrt_price_from_input,
     get_next_sqrt_price_from_output, mul_div, mul_div_rounding_up,
 };
+use crate::error::Error;
 use alloy_primitives::{I256, U256};
-use uniswap_v3_math::error::UniswapV3MathError;
 
 /// Computes the result of swapping some amount in, or amount out, given the parameters of the swap
 ///
@@ -30,7 +30,7 @@ pub fn compute_swap_step(
     liquidity: u128,
     amount_remaining: I256,
     fee_pips: u32,
-) -> Result<(U256, U256, U256, U256), UniswapV3MathError> {
+) -> Result<(U256, U256, U256, U256), Error> {
     const MAX_FEE: U256 = U256::from_limbs([1000000, 0, 0, 0]);
     let fee_pips = U256::from_limbs([fee_pips as u64, 0, 0, 0]);
     let fee_complement = MAX_FEE - fee_pips;
@@ -134,8 +134,175 @@ pub fn compute_swap_step(
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use crate::utils::encode_sqrt_ratio_x96;
+
+    const ONE_ETHER: i128 = 1_000_000_000_000_000_000;
+
+    fn u(x: u128) -> U256 {
+        U256::from(x)
+    }
+
+    #[test]
+    fn test_exact_amount_in_capped_at_price_target_in_one_for_zero() {
+        let price = encode_sqrt_ratio_x96(1, 1);
+        let price_target = encode_sqrt_ratio_x96(101, 100);
+        let (sqrt_q, amount_in, amount_out, fee_amount) = compute_swap_step(
+            price,
+            price_target,
+            2 * ONE_ETHER as u128,
+            I256::try_from(ONE_ETHER).unwrap(),
+            600,
+        )
+        .unwrap();
+        assert_eq!(amount_in, u(9975124224178055));
+        assert_eq!(fee_amount, u(5988667735148));
+        assert_eq!(amount_out, u(9925619580021728));
+        assert!(amount_in + fee_amount < u(ONE_ETHER as u128));
+        assert_eq!(sqrt_q, price_target);
+    }
+
+    #[test]
+    fn test_exact_amount_out_capped_at_price_target_in_one_for_zero() {
+        let price = encode_sqrt_ratio_x96(1, 1);
+        let price_target = encode_sqrt_ratio_x96(101, 100);
+        let (sqrt_q, amount_in, amount_out, fee_amount) = compute_swap_step(
+            price,
+            price_target,
+            2 * ONE_ETHER as u128,
+            I256::try_from(-ONE_ETHER).unwrap(),
+            600,
+        )
+        .unwrap();
+        assert_eq!(amount_in, u(9975124224178055));
+        assert_eq!(fee_amount, u(5988667735148));
+        assert_eq!(amount_out, u(9925619580021728));
+        assert!(amount_out < u(ONE_ETHER as u128));
+        assert_eq!(sqrt_q, price_target);
+    }
+
+    #[test]
+    fn test_exact_amount_in_fully_spent_in_one_for_zero() {
+        let price = encode_sqrt_ratio_x96(1, 1);
+        let price_target = encode_sqrt_ratio_x96(1000, 100);
+        let (sqrt_q, amount_in, amount_out, fee_amount) = compute_swap_step(
+            price,
+            price_target,
+            2 * ONE_ETHER as u128,
+            I256::try_from(ONE_ETHER).unwrap(),
+            600,
+        )
+        .unwrap();
+        assert_eq!(amount_in, u(999400000000000000));
+        assert_eq!(fee_amount, u(600000000000000));
+        assert_eq!(amount_out, u(666399946655997866));
+        assert_eq!(amount_in + fee_amount, u(ONE_ETHER as u128));
+        assert!(sqrt_q < price_target);
+    }
+
+    #[test]
+    fn test_exact_amount_out_fully_received_in_one_for_zero() {
+        let price = encode_sqrt_ratio_x96(1, 1);
+        let price_target = encode_sqrt_ratio_x96(10000, 100);
+        let (sqrt_q, amount_in, amount_out, fee_amount) = compute_swap_step(
+            price,
+            price_target,
+            2 * ONE_ETHER as u128,
+            I256::try_from(-ONE_ETHER).unwrap(),
+            600,
+        )
+        .unwrap();
+        assert_eq!(amount_in, u(2000000000000000000));
+        assert_eq!(fee_amount, u(1200720432259356));
+        assert_eq!(amount_out, u(ONE_ETHER as u128));
+        assert!(sqrt_q < price_target);
+    }
+
+    #[test]
+    fn test_amount_out_is_capped_at_the_desired_amount_out() {
+        let (sqrt_q, amount_in, amount_out, fee_amount) = compute_swap_step(
+            u(417332158212080721273783715441582),
+            u(1452870262520218020823638996),
+            159344665391607089467575320103,
+            I256::MINUS_ONE,
+            1,
+        )
+        .unwrap();
+        assert_eq!(amount_in, u(1));
+        assert_eq!(fee_amount, u(1));
+        assert_eq!(amount_out, u(1));
+        assert_eq!(sqrt_q, u(417332158212080721273783715441581));
+    }
+
+    #[test]
+    fn test_target_price_of_1_uses_partial_input_amount() {
+        let (sqrt_q, amount_in, amount_out, fee_amount) = compute_swap_step(
+            u(2),
+            u(1),
+            1,
+            I256::try_from(3915081100057732413702495386755767_u128).unwrap(),
+            1,
+        )
+        .unwrap();
+        assert_eq!(amount_in, u(39614081257132168796771975168));
+        assert_eq!(fee_amount, u(39614120871253040049813));
+        assert!(amount_in + fee_amount <= u(3915081100057732413702495386755767));
+        assert_eq!(amount_out, U256::ZERO);
+        assert_eq!(sqrt_q, u(1));
+    }
+
+    #[test]
+    fn test_entire_input_amount_taken_as_fee() {
+        let (sqrt_q, amount_in, amount_out, fee_amount) = compute_swap_step(
+            u(2413),
+            u(79887613182836312),
+            1985041575832132834610021537970,
+            I256::try_from(10).unwrap(),
+            1872,
+        )
+        .unwrap();
+        // v3-core reports (0, 10) here; like uni-v3-lib, the input that doesn't move the price is
+        // counted towards `amount_in` and only the remainder is taken as fee
+        assert_eq!(amount_in, u(9));
+        assert_eq!(fee_amount, u(1));
+        assert_eq!(amount_in + fee_amount, u(10));
+        assert_eq!(amount_out, U256::ZERO);
+        assert_eq!(sqrt_q, u(2413));
+    }
+
+    #[test]
+    fn test_intermediate_insufficient_liquidity_in_zero_for_one_exact_output() {
+        let sqrt_p = u(20282409603651670423947251286016);
+        let sqrt_p_target = sqrt_p * u(11) / u(10);
+        let (sqrt_q, amount_in, amount_out, fee_amount) = compute_swap_step(
+            sqrt_p,
+            sqrt_p_target,
+            1024,
+            I256::try_from(-4).unwrap(),
+            3000,
+        )
+        .unwrap();
+        assert_eq!(amount_out, U256::ZERO);
+        assert_eq!(sqrt_q, sqrt_p_target);
+        assert_eq!(amount_in, u(26215));
+        assert_eq!(fee_amount, u(79));
+    }
+
     #[test]
-    fn test_compute_swap_step() {
-        // TODO: Add more tests
+    fn test_intermediate_insufficient_liquidity_in_one_for_zero_exact_output() {
+        let sqrt_p = u(20282409603651670423947251286016);
+        let sqrt_p_target = sqrt_p * u(9) / u(10);
+        let (sqrt_q, amount_in, amount_out, fee_amount) = compute_swap_step(
+            sqrt_p,
+            sqrt_p_target,
+            1024,
+            I256::try_from(-263000).unwrap(),
+            3000,
+        )
+        .unwrap();
+        assert_eq!(amount_out, u(26214));
+        assert_eq!(sqrt_q, sqrt_p_target);
+        assert_eq!(amount_in, u(1));
+        assert_eq!(fee_amount, u(1));
     }
 }
@@ -238,6 +238,21 @@ pub fn get_tick_at_sqrt_ratio(sqrt_ratio_x96: U256) -> Result<i32, UniswapV3Math
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn get_tick_at_sqrt_ratio_inverts_get_sqrt_ratio_at_tick(tick in MIN_TICK..MAX_TICK) {
+            let sqrt_ratio = get_sqrt_ratio_at_tick(tick).unwrap();
+            prop_assert_eq!(get_tick_at_sqrt_ratio(sqrt_ratio).unwrap(), tick);
+            // the tick is the greatest tick whose sqrt ratio is at most the given sqrt ratio
+            let next_sqrt_ratio = get_sqrt_ratio_at_tick(tick + 1).unwrap();
+            prop_assert_eq!(
+                get_tick_at_sqrt_ratio(next_sqrt_ratio - U256::from(1)).unwrap(),
+                tick
+            );
+        }
+    }
 
     #[test]
     fn min_tick() {